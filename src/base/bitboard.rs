@@ -17,19 +17,27 @@
 */
 
 //! Bitboards, data structures used to efficiently represent sets of squares.
+//!
+//! Like `Square` and `Direction`, which it is built on, this module only uses `core`, so that it
+//! can be reused in `no_std` contexts.
+//! Clippy warns about any item taken from `std` which is also available in `core`, and an example
+//! in the documentation of the `base` module builds all three modules into a `no_std` crate.
 
-use std::{
+#![warn(clippy::std_instead_of_core, clippy::std_instead_of_alloc)]
+
+use core::{
     fmt::{Display, Formatter, Result},
-    iter::Iterator,
+    iter::{FromIterator, Iterator},
     mem::transmute,
     ops::{
         BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, ShlAssign, Shr,
+        Sub, SubAssign,
     },
 };
 
-use super::{magic::directional_attacks, Direction, Square};
+use super::{Direction, Square};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 /// A bitboard, which uses an integer to express a set of `Square`s.
 /// This expression allows the efficient computation of set intersection, union, disjunction,
@@ -77,6 +85,72 @@ impl Bitboard {
     /// ```
     pub const ALL: Bitboard = Bitboard::new(!0);
 
+    /// The squares on each file, indexed from the A-file (0) to the H-file (7).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert!(Bitboard::FILES[4].contains(Square::E4));
+    /// assert!(!Bitboard::FILES[4].contains(Square::D4));
+    /// ```
+    pub const FILES: [Bitboard; 8] = {
+        let mut files = [Bitboard::EMPTY; 8];
+        let mut i = 0;
+        while i < 8 {
+            files[i] = Bitboard::new(0x0101_0101_0101_0101 << i);
+            i += 1;
+        }
+        files
+    };
+
+    /// The squares on each rank, indexed from rank 1 (0) to rank 8 (7).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert!(Bitboard::RANKS[3].contains(Square::E4));
+    /// assert!(!Bitboard::RANKS[3].contains(Square::E5));
+    /// ```
+    pub const RANKS: [Bitboard; 8] = {
+        let mut ranks = [Bitboard::EMPTY; 8];
+        let mut i = 0;
+        while i < 8 {
+            ranks[i] = Bitboard::new(0xFF << (8 * i));
+            i += 1;
+        }
+        ranks
+    };
+
+    /// The four central squares: d4, e4, d5, and e5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert_eq!(Bitboard::CENTER.len(), 4);
+    /// assert!(Bitboard::CENTER.contains(Square::E4));
+    /// ```
+    pub const CENTER: Bitboard =
+        Bitboard::from_squares(&[Square::D4, Square::E4, Square::D5, Square::E5]);
+
+    /// The sixteen squares from c3 to f6, including the four central squares.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert_eq!(Bitboard::EXTENDED_CENTER.len(), 16);
+    /// assert!(Bitboard::EXTENDED_CENTER.contains(Square::C6));
+    /// assert_eq!(Bitboard::CENTER & Bitboard::EXTENDED_CENTER, Bitboard::CENTER);
+    /// ```
+    pub const EXTENDED_CENTER: Bitboard = Bitboard::CENTER.expand();
+
     #[inline(always)]
     #[must_use]
     /// Construct a new Bitboard from a numeric literal.
@@ -97,6 +171,29 @@ impl Bitboard {
         Bitboard(x)
     }
 
+    #[must_use]
+    /// Construct a new Bitboard containing exactly the squares in `sqs`.
+    /// Unlike collecting from an iterator, this can be used to define constants.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// const CORNERS: Bitboard = Bitboard::from_squares(&[Square::A1, Square::H8]);
+    /// assert!(CORNERS.contains(Square::H8));
+    /// assert_eq!(CORNERS.len(), 2);
+    /// ```
+    pub const fn from_squares(sqs: &[Square]) -> Bitboard {
+        let mut x = 0;
+        let mut i = 0;
+        while i < sqs.len() {
+            x |= 1 << sqs[i] as u8;
+            i += 1;
+        }
+        Bitboard(x)
+    }
+
     #[inline(always)]
     #[must_use]
     /// Determine whether this bitboard contains a given square.
@@ -184,6 +281,255 @@ impl Bitboard {
         self.0.leading_zeros()
     }
 
+    #[inline(always)]
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    /// Get the lowest square (i.e. the one closest to A1) in this bitboard, or `None` if it is
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// let bb = Bitboard::from(Square::E4).with_square(Square::B7);
+    /// assert_eq!(bb.lsb(), Some(Square::E4));
+    /// assert_eq!(Bitboard::EMPTY.lsb(), None);
+    /// ```
+    pub const fn lsb(self) -> Option<Square> {
+        if self.is_empty() {
+            return None;
+        }
+        // SAFETY: The empty bitboard case has been handled already, so the number of trailing zeros
+        // is between 0 and 63.
+        Some(unsafe { transmute(self.trailing_zeros() as u8) })
+    }
+
+    #[inline(always)]
+    /// Remove the lowest square from this bitboard and return it, or return `None` if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// let mut bb = Bitboard::from(Square::E4).with_square(Square::B7);
+    /// assert_eq!(bb.pop_lsb(), Some(Square::E4));
+    /// assert_eq!(bb, Bitboard::from(Square::B7));
+    /// ```
+    pub fn pop_lsb(&mut self) -> Option<Square> {
+        let sq = self.lsb()?;
+        // clear the lowest set bit
+        self.0 &= self.0 - 1;
+        Some(sq)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Shift every square in this bitboard one rank north (toward rank 8).
+    /// Squares on rank 8 fall off the board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert_eq!(Bitboard::from(Square::E4).north(), Bitboard::from(Square::E5));
+    /// assert_eq!(Bitboard::from(Square::E8).north(), Bitboard::EMPTY);
+    /// ```
+    pub const fn north(self) -> Bitboard {
+        Bitboard(self.0 << 8)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Shift every square in this bitboard one rank south (toward rank 1).
+    /// Squares on rank 1 fall off the board.
+    pub const fn south(self) -> Bitboard {
+        Bitboard(self.0 >> 8)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Shift every square in this bitboard one file east (toward the H-file).
+    /// Squares on the H-file fall off the board instead of wrapping around to the A-file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert_eq!(Bitboard::from(Square::E4).east(), Bitboard::from(Square::F4));
+    /// assert_eq!(Bitboard::from(Square::H4).east(), Bitboard::EMPTY);
+    /// ```
+    pub const fn east(self) -> Bitboard {
+        Bitboard((self.0 & !Bitboard::FILES[7].0) << 1)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Shift every square in this bitboard one file west (toward the A-file).
+    /// Squares on the A-file fall off the board instead of wrapping around to the H-file.
+    pub const fn west(self) -> Bitboard {
+        Bitboard((self.0 & !Bitboard::FILES[0].0) >> 1)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Shift every square in this bitboard one square northeast, without wrapping.
+    pub const fn northeast(self) -> Bitboard {
+        self.east().north()
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Shift every square in this bitboard one square northwest, without wrapping.
+    pub const fn northwest(self) -> Bitboard {
+        self.west().north()
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Shift every square in this bitboard one square southeast, without wrapping.
+    pub const fn southeast(self) -> Bitboard {
+        self.east().south()
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Shift every square in this bitboard one square southwest, without wrapping.
+    pub const fn southwest(self) -> Bitboard {
+        self.west().south()
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Flip this bitboard vertically, swapping rank 1 with rank 8, rank 2 with rank 7, and so on.
+    /// This is the same as calling `Square::opposite()` on every square.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert_eq!(Bitboard::from(Square::A1).flip_vertical(), Bitboard::from(Square::A8));
+    /// ```
+    pub const fn flip_vertical(self) -> Bitboard {
+        // each rank is one byte
+        Bitboard(self.0.swap_bytes())
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Flip this bitboard horizontally, swapping the A file with the H file, the B file with the G
+    /// file, and so on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert_eq!(Bitboard::from(Square::A1).flip_horizontal(), Bitboard::from(Square::H1));
+    /// ```
+    pub const fn flip_horizontal(self) -> Bitboard {
+        // reversing every bit also reverses the order of the ranks, so put them back
+        Bitboard(self.0.reverse_bits().swap_bytes())
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Rotate this bitboard by 180 degrees, which flips it both vertically and horizontally.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert_eq!(Bitboard::from(Square::B1).rotate_180(), Bitboard::from(Square::G8));
+    /// ```
+    pub const fn rotate_180(self) -> Bitboard {
+        Bitboard(self.0.reverse_bits())
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Smear every square in this bitboard north, to the end of its file.
+    /// For a white pawn, this gives the square it stands on and its entire front span.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// let fill = Bitboard::from(Square::A6).north_fill();
+    /// assert_eq!(fill, Bitboard::from_iter([Square::A6, Square::A7, Square::A8]));
+    /// ```
+    pub const fn north_fill(self) -> Bitboard {
+        // Kogge-Stone fill: each step doubles the distance covered
+        let mut x = self.0;
+        x |= x << 8;
+        x |= x << 16;
+        x |= x << 32;
+        Bitboard(x)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Smear every square in this bitboard south, to the end of its file.
+    /// For a black pawn, this gives the square it stands on and its entire front span.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// let fill = Bitboard::from(Square::C3).south_fill();
+    /// assert_eq!(fill, Bitboard::from_iter([Square::C1, Square::C2, Square::C3]));
+    /// ```
+    pub const fn south_fill(self) -> Bitboard {
+        let mut x = self.0;
+        x |= x >> 8;
+        x |= x >> 16;
+        x |= x >> 32;
+        Bitboard(x)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Get every file which contains a square in this bitboard.
+    /// For a set of pawns, the complement of this is the set of files with no pawns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert_eq!(Bitboard::from(Square::E4).file_fill(), Bitboard::FILES[4]);
+    /// ```
+    pub const fn file_fill(self) -> Bitboard {
+        Bitboard(self.north_fill().0 | self.south_fill().0)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Expand this bitboard by one square in every direction, giving the union of each square and
+    /// its eight neighbors.
+    /// Expanding the square of a king gives its king zone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// assert_eq!(Bitboard::from(Square::E4).expand().len(), 9);
+    /// assert_eq!(Bitboard::from(Square::A1).expand().len(), 4);
+    /// ```
+    pub const fn expand(self) -> Bitboard {
+        // smear east and west first, so that the north and south shifts pick up the corners
+        let row = Bitboard(self.0 | self.east().0 | self.west().0);
+        Bitboard(row.0 | row.north().0 | row.south().0)
+    }
+
     #[must_use]
     #[inline(always)]
     /// Determine whether this bitboard is empty.
@@ -621,6 +967,24 @@ bb_binassign_define!(BitAndAssign, bitand_assign, &=);
 bb_binassign_define!(BitOrAssign, bitor_assign, |=);
 bb_binassign_define!(BitXorAssign, bitxor_assign, ^=);
 
+impl Sub for Bitboard {
+    type Output = Self;
+
+    #[inline(always)]
+    /// Compute the set difference of two bitboards, i.e. the squares in `self` which are not in
+    /// `rhs`.
+    fn sub(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 & !rhs.0)
+    }
+}
+
+impl SubAssign for Bitboard {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 &= !rhs.0;
+    }
+}
+
 impl Shl<u8> for Bitboard {
     type Output = Self;
 
@@ -662,6 +1026,32 @@ impl From<Square> for Bitboard {
     }
 }
 
+impl FromIterator<Square> for Bitboard {
+    /// Construct a bitboard containing every square yielded by an iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Square};
+    ///
+    /// let bb: Bitboard = [Square::A1, Square::H8].into_iter().collect();
+    /// assert_eq!(bb.len(), 2);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = Square>>(iter: I) -> Self {
+        let mut bb = Bitboard::EMPTY;
+        for sq in iter {
+            bb.insert(sq);
+        }
+        bb
+    }
+}
+
+impl From<Bitboard> for u64 {
+    fn from(bb: Bitboard) -> Self {
+        bb.0
+    }
+}
+
 impl From<Bitboard> for usize {
     fn from(bb: Bitboard) -> Self {
         #[allow(clippy::cast_possible_truncation)]
@@ -694,17 +1084,8 @@ impl Iterator for Bitboard {
     type Item = Square;
 
     #[inline(always)]
-    #[allow(clippy::cast_possible_truncation)]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.is_empty() {
-            return None;
-        }
-        let trailing = self.trailing_zeros() as u8;
-        // SAFETY: The empty bitboard case has been handled already, so the number of trailing zeros
-        // is between 0 and 63.
-        let result = Some(unsafe { transmute(trailing) });
-        self.0 ^= 1 << trailing;
-        result
+        self.pop_lsb()
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -712,3 +1093,365 @@ impl Iterator for Bitboard {
         (n, Some(n))
     }
 }
+
+/// Construct the squares attacked by the pieces at `sq` if it could move along the directions in
+/// `dirs` when the board is occupied by the pieces in `occupancy`.
+///
+/// This is slow and should only be used for generatic magic bitboards (instead of for move
+/// generation.
+pub(crate) const fn directional_attacks(
+    sq: Square,
+    dirs: &[Direction],
+    occupancy: Bitboard,
+) -> Bitboard {
+    // behold: much hackery for making this work as a const fn
+    let mut result = Bitboard::EMPTY;
+    let mut dir_idx = 0;
+    while dir_idx < dirs.len() {
+        let dir = dirs[dir_idx];
+        let mut current_square = sq;
+        let mut loop_idx = 0;
+        while loop_idx < 7 {
+            let next_square_int: i16 = current_square as i16
+                + unsafe {
+                    // SAFETY: All values for an `i8` are valid.
+                    transmute::<Direction, i8>(dir) as i16
+                };
+            if next_square_int < 0 || 64 <= next_square_int {
+                break;
+            }
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let next_square: Square = unsafe {
+                // SAFETY: We checked that this next square was in the range 0..63, which is how a
+                // square is represented.
+                transmute(next_square_int as u8)
+            };
+            if next_square.chebyshev_to(current_square) > 1 {
+                break;
+            }
+            result = Bitboard::new(
+                unsafe {
+                    // SAFETY: Any value is OK for an int.
+                    transmute::<Bitboard, u64>(result)
+                } | 1 << next_square as u8,
+            );
+            if occupancy.contains(next_square) {
+                break;
+            }
+            current_square = next_square;
+            loop_idx += 1;
+        }
+        dir_idx += 1;
+    }
+
+    result
+}
+
+#[cfg(feature = "serde")]
+/// Bitboards are serialized as their inner `u64`.
+impl serde::Serialize for Bitboard {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Bitboard {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Bitboard, D::Error> {
+        <u64 as serde::Deserialize>::deserialize(deserializer).map(Bitboard::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    /// Test that iterating over a bitboard yields its squares in ascending order.
+    fn iterate_squares() {
+        let bb = Bitboard::new(0x8000_0000_0000_0301);
+        assert_eq!(
+            bb.collect::<Vec<Square>>(),
+            vec![Square::A1, Square::A2, Square::B2, Square::H8]
+        );
+        let bb = Bitboard::EMPTY
+            .with_square(Square::E4)
+            .with_square(Square::D5);
+        assert_eq!(bb.collect::<Vec<Square>>(), vec![Square::E4, Square::D5]);
+    }
+
+    #[test]
+    /// Test that an empty bitboard yields nothing and a full one yields every square in order.
+    fn iterate_empty_and_full() {
+        let mut empty = Bitboard::EMPTY;
+        assert_eq!(empty.next(), None);
+        let squares: Vec<Square> = Bitboard::ALL.collect();
+        assert_eq!(squares.len(), 64);
+        for (i, &sq) in squares.iter().enumerate() {
+            assert_eq!(sq as usize, i);
+        }
+    }
+
+    #[test]
+    /// Test that `len` and `is_empty` count squares correctly.
+    fn len_counts_squares() {
+        assert_eq!(Bitboard::EMPTY.len(), 0);
+        assert!(Bitboard::EMPTY.is_empty());
+        assert_eq!(Bitboard::from(Square::E4).len(), 1);
+        assert!(!Bitboard::from(Square::E4).is_empty());
+        assert_eq!(Bitboard::ALL.len(), 64);
+    }
+
+    #[test]
+    /// Test that negating a bitboard takes its complement.
+    fn not_complement() {
+        assert_eq!(!Bitboard::EMPTY, Bitboard::new(u64::MAX));
+        assert_eq!(!Bitboard::ALL, Bitboard::EMPTY);
+        let bb = Bitboard::new(0x0000_00FF_1234_0000);
+        assert_eq!(!!bb, bb);
+        assert!((bb & !bb).is_empty());
+    }
+
+    #[test]
+    /// Test that the assigning operators accumulate squares like their non-assigning versions.
+    fn assign_ops() {
+        let mut acc = Bitboard::EMPTY;
+        for sq in [Square::A1, Square::E4, Square::H8] {
+            acc |= Bitboard::from(sq);
+        }
+        assert_eq!(
+            acc,
+            Bitboard::from(Square::A1) | Bitboard::from(Square::E4) | Bitboard::from(Square::H8)
+        );
+
+        acc &= !Bitboard::from(Square::E4);
+        assert_eq!(acc.len(), 2);
+        assert!(!acc.contains(Square::E4));
+
+        acc ^= Bitboard::from(Square::A1);
+        assert_eq!(acc, Bitboard::from(Square::H8));
+    }
+
+    #[test]
+    /// Test that repeatedly popping the lowest square yields squares in ascending order and leaves
+    /// the bitboard empty.
+    fn pop_lsb_ascending() {
+        let mut bb = Bitboard::new(0x0081_0000_0010_0402);
+        let mut popped = Vec::new();
+        while let Some(sq) = bb.pop_lsb() {
+            popped.push(sq);
+        }
+        assert_eq!(
+            popped,
+            vec![Square::B1, Square::C2, Square::E3, Square::A7, Square::H7]
+        );
+        assert!(bb.is_empty());
+        assert_eq!(bb.lsb(), None);
+        assert_eq!(bb.pop_lsb(), None);
+    }
+
+    #[test]
+    /// Test that a bitboard is displayed as a grid with rank 8 at the top and the A-file on the
+    /// left.
+    fn display_grid() {
+        // each square is followed by a space, and each rank by a newline
+        let expected = format!("{}1 . . . . . . . \n", ". . . . . . . . \n".repeat(7));
+        assert_eq!(Bitboard::from(Square::A1).to_string(), expected);
+        let h8 = Bitboard::from(Square::H8).to_string();
+        assert!(h8.starts_with(". . . . . . . 1 \n"));
+    }
+
+    #[test]
+    /// Test that the file and rank masks intersect on exactly one square.
+    fn file_rank_masks() {
+        assert_eq!(
+            Bitboard::FILES[0] & Bitboard::RANKS[0],
+            Bitboard::from(Square::A1)
+        );
+        assert_eq!(
+            Bitboard::FILES[7] & Bitboard::RANKS[7],
+            Bitboard::from(Square::H8)
+        );
+        let files = Bitboard::FILES
+            .iter()
+            .fold(Bitboard::EMPTY, |acc, &f| acc | f);
+        let ranks = Bitboard::RANKS
+            .iter()
+            .fold(Bitboard::EMPTY, |acc, &r| acc | r);
+        assert_eq!(files, Bitboard::ALL);
+        assert_eq!(ranks, Bitboard::ALL);
+    }
+
+    #[test]
+    /// Test that subtracting bitboards computes the set difference.
+    fn sub_difference() {
+        assert_eq!(Bitboard::ALL - Bitboard::FILES[0], !Bitboard::FILES[0]);
+        assert_eq!(Bitboard::FILES[0] - Bitboard::ALL, Bitboard::EMPTY);
+
+        let mut bb = Bitboard::RANKS[0];
+        bb -= Bitboard::FILES[0];
+        assert_eq!(bb.len(), 7);
+        assert!(!bb.contains(Square::A1));
+    }
+
+    #[test]
+    /// Test that shifting a file east or west moves it to the adjacent file without wrapping.
+    fn shift_files() {
+        assert_eq!(Bitboard::FILES[0].east(), Bitboard::FILES[1]);
+        assert_eq!(Bitboard::FILES[7].east(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::FILES[1].west(), Bitboard::FILES[0]);
+        assert_eq!(Bitboard::FILES[0].west(), Bitboard::EMPTY);
+    }
+
+    #[test]
+    /// Test that shifting ranks and diagonals moves squares in the right direction.
+    fn shift_ranks_and_diagonals() {
+        assert_eq!(Bitboard::RANKS[0].north(), Bitboard::RANKS[1]);
+        assert_eq!(Bitboard::RANKS[7].north(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::RANKS[0].south(), Bitboard::EMPTY);
+
+        let e4 = Bitboard::from(Square::E4);
+        assert_eq!(e4.northeast(), Bitboard::from(Square::F5));
+        assert_eq!(e4.northwest(), Bitboard::from(Square::D5));
+        assert_eq!(e4.southeast(), Bitboard::from(Square::F3));
+        assert_eq!(e4.southwest(), Bitboard::from(Square::D3));
+        assert_eq!(Bitboard::from(Square::H4).northeast(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::from(Square::A4).southwest(), Bitboard::EMPTY);
+    }
+
+    #[test]
+    /// Test the vertical and horizontal flips and the 180-degree rotation on individual squares and
+    /// whole files and ranks.
+    fn flips() {
+        let a1 = Bitboard::from(Square::A1);
+        assert_eq!(a1.flip_vertical(), Bitboard::from(Square::A8));
+        assert_eq!(a1.flip_horizontal(), Bitboard::from(Square::H1));
+        assert_eq!(a1.rotate_180(), Bitboard::from(Square::H8));
+
+        for sq in Bitboard::ALL {
+            let bb = Bitboard::from(sq);
+            assert_eq!(bb.flip_vertical(), Bitboard::from(sq.opposite()));
+            assert_eq!(bb.flip_vertical().flip_vertical(), bb);
+            assert_eq!(bb.flip_horizontal().flip_horizontal(), bb);
+            assert_eq!(bb.rotate_180(), bb.flip_vertical().flip_horizontal());
+        }
+
+        assert_eq!(Bitboard::FILES[1].flip_horizontal(), Bitboard::FILES[6]);
+        assert_eq!(Bitboard::RANKS[1].flip_vertical(), Bitboard::RANKS[6]);
+        assert_eq!(Bitboard::RANKS[1].flip_horizontal(), Bitboard::RANKS[1]);
+    }
+
+    #[test]
+    /// Test that north and south fills smear a square to the end of its file and nowhere else.
+    fn fills() {
+        let a4 = Bitboard::from(Square::A4);
+        let north: Bitboard = [Square::A4, Square::A5, Square::A6, Square::A7, Square::A8]
+            .into_iter()
+            .collect();
+        assert_eq!(a4.north_fill(), north);
+        assert_eq!(
+            a4.south_fill(),
+            [Square::A1, Square::A2, Square::A3, Square::A4]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(a4.file_fill(), Bitboard::FILES[0]);
+
+        // filling several squares at once fills each of their files independently
+        let bb: Bitboard = [Square::B2, Square::G7].into_iter().collect();
+        assert_eq!(bb.file_fill(), Bitboard::FILES[1] | Bitboard::FILES[6]);
+        assert_eq!(Bitboard::EMPTY.north_fill(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::RANKS[0].north_fill(), Bitboard::ALL);
+    }
+
+    #[test]
+    /// Test that expanding a square gives its one-square neighborhood, clipped at the edges.
+    fn expand_ring() {
+        let e4 = Bitboard::from(Square::E4).expand();
+        assert_eq!(e4.len(), 9);
+        assert_eq!(
+            e4,
+            (Bitboard::FILES[3] | Bitboard::FILES[4] | Bitboard::FILES[5])
+                & (Bitboard::RANKS[2] | Bitboard::RANKS[3] | Bitboard::RANKS[4])
+        );
+
+        let a1: Bitboard = [Square::A1, Square::B1, Square::A2, Square::B2]
+            .into_iter()
+            .collect();
+        assert_eq!(Bitboard::from(Square::A1).expand(), a1);
+        assert_eq!(Bitboard::from(Square::H8).expand().len(), 4);
+        assert_eq!(Bitboard::from(Square::H4).expand().len(), 6);
+        assert_eq!(Bitboard::EMPTY.expand(), Bitboard::EMPTY);
+    }
+
+    #[test]
+    /// Test that building a bitboard from a list of squares matches adding them one at a time.
+    fn from_squares_matches_fold() {
+        let sqs = [Square::A1, Square::E4, Square::E4, Square::H8, Square::C6];
+        let folded = sqs
+            .iter()
+            .fold(Bitboard::EMPTY, |bb, &sq| bb | Bitboard::from(sq));
+        assert_eq!(Bitboard::from_squares(&sqs), folded);
+        assert_eq!(Bitboard::from_squares(&[]), Bitboard::EMPTY);
+    }
+
+    #[test]
+    /// Test that the center constants contain exactly the squares they claim to.
+    fn center_squares() {
+        assert_eq!(
+            Bitboard::CENTER,
+            Bitboard::from_squares(&[Square::D4, Square::E4, Square::D5, Square::E5])
+        );
+        let mut extended = Bitboard::EMPTY;
+        for rank in 2..6 {
+            for file in 2..6 {
+                extended.insert(Square::new(rank, file).unwrap());
+            }
+        }
+        assert_eq!(Bitboard::EXTENDED_CENTER, extended);
+    }
+
+    #[test]
+    /// Test that collecting squares into a bitboard contains exactly those squares.
+    fn collect_squares() {
+        let bb: Bitboard = [Square::A1, Square::H8].into_iter().collect();
+        assert!(bb.contains(Square::A1));
+        assert!(bb.contains(Square::H8));
+        assert!(!bb.contains(Square::E4));
+        assert_eq!(bb.collect::<Bitboard>(), bb);
+    }
+
+    #[test]
+    /// Test that equal bitboards are deduplicated in a `HashSet`, and that the default bitboard is
+    /// empty.
+    fn hash_set_dedup() {
+        let set: HashSet<Bitboard> = [
+            Bitboard::from(Square::E4),
+            Bitboard::EMPTY,
+            Bitboard::from(Square::E4),
+            Bitboard::default(),
+            Bitboard::ALL,
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&Bitboard::new(1 << Square::E4 as u8)));
+        assert_eq!(Bitboard::default(), Bitboard::EMPTY);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    /// Test that a bitboard survives a round trip through JSON, serialized as its inner integer.
+    fn serde_round_trip() {
+        let bb = Bitboard::new(0x0000_0010_0800_FF00);
+        let json = serde_json::to_string(&bb).unwrap();
+        assert_eq!(json, 0x0000_0010_0800_FF00u64.to_string());
+        assert_eq!(serde_json::from_str::<Bitboard>(&json).unwrap(), bb);
+    }
+}
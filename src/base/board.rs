@@ -20,7 +20,7 @@
 //! positions), but neither history nor meta-information about a game.
 
 use super::{
-    movegen::{square_attackers, PAWN_ATTACKS},
+    movegen::{square_attackers, KING_MOVES, KNIGHT_MOVES, PAWN_ATTACKS},
     MAGIC,
 };
 
@@ -34,6 +34,10 @@ use std::{
     ops::Index,
 };
 
+/// The files of the castling rooks in standard chess, in the same order as the bits of a
+/// `CastleRights`.
+const STANDARD_ROOK_FILES: [u8; 4] = [7, 0, 7, 0];
+
 #[derive(Copy, Clone, Debug, Eq)]
 /// A representation of a position. Does not handle repetition of moves.
 pub struct Board {
@@ -50,6 +54,11 @@ pub struct Board {
     pub en_passant_square: Option<Square>,
     /// The rights of this piece for castling.
     pub castle_rights: CastleRights,
+    /// The file of the rook which each castling right refers to, in the same order as the bits of
+    /// `castle_rights`.
+    /// In standard chess these are the corner files, but in Chess960 a castling rook may start on
+    /// any file on its side of the king.
+    castle_rook_files: [u8; 4],
     /// The number of plies that have passed since a capture or pawn push has been made.
     rule50: u8,
 
@@ -61,6 +70,10 @@ pub struct Board {
     /// If the board is valid, the this value must ALWAYS be equal to the output
     /// of `Board.get_fresh_hash()`.
     pub hash: u64,
+    /// A saved hash of only the pawns on this board.
+    /// If the board is valid, this value must always be equal to the output of
+    /// `Board.get_fresh_pawn_hash()`.
+    pawn_hash: u64,
     /// The set of squares which is occupied by pieces which are checking the
     /// king.
     pub checkers: Bitboard,
@@ -93,8 +106,10 @@ impl Board {
             en_passant_square: None,
             player: Color::White,
             castle_rights: CastleRights::ALL,
+            castle_rook_files: STANDARD_ROOK_FILES,
             rule50: 0,
             hash: 0,
+            pawn_hash: 0,
             king_sqs: [Square::E1, Square::E8],
             checkers: Bitboard::EMPTY,
             pinned: Bitboard::EMPTY,
@@ -129,8 +144,10 @@ impl Board {
             en_passant_square: None,
             player: Color::White,
             castle_rights: CastleRights::NONE,
+            castle_rook_files: STANDARD_ROOK_FILES,
             rule50: 0,
             hash: 0,
+            pawn_hash: 0,
             checkers: Bitboard::EMPTY,
             king_sqs: [Square::A1; 2],
             pinned: Bitboard::EMPTY,
@@ -203,14 +220,31 @@ impl Board {
             .ok_or("reached end of string while parsing castle rights")?;
         while castle_chr != ' ' {
             // this may accept some technically illegal FENS, but that's ok
-            board.castle_rights |= match castle_chr {
-                'K' => CastleRights::WHITE_KINGSIDE,
-                'Q' => CastleRights::WHITE_QUEENSIDE,
-                'k' => CastleRights::BLACK_KINGSIDE,
-                'q' => CastleRights::BLACK_QUEENSIDE,
-                '-' => CastleRights::NONE,
-                _ => return Err("unrecognized castle rights character"),
-            };
+            // `K` and `Q` refer to the outermost rook on their side of the king, while a file
+            // letter (as in Shredder-FEN) names the rook's file directly, for Chess960
+            if castle_chr != '-' {
+                let color = if castle_chr.is_ascii_uppercase() {
+                    Color::White
+                } else {
+                    Color::Black
+                };
+                let (kingside, rook_file) = match castle_chr.to_ascii_lowercase() {
+                    'k' => (true, board.outermost_rook_file(color, true).unwrap_or(7)),
+                    'q' => (false, board.outermost_rook_file(color, false).unwrap_or(0)),
+                    c @ 'a'..='h' => {
+                        let rook_file = c as u8 - b'a';
+                        let king_file = board
+                            .back_rank_king(color)
+                            .ok_or("castle rights given without a king on the back rank")?
+                            .file();
+                        (rook_file > king_file, rook_file)
+                    }
+                    _ => return Err("unrecognized castle rights character"),
+                };
+                let i = 2 * color as usize + usize::from(!kingside);
+                board.castle_rights |= CastleRights(1 << i);
+                board.castle_rook_files[i] = rook_file;
+            }
             castle_chr = fen_chrs
                 .next()
                 .ok_or("reached end of string while parsing castle rights")?;
@@ -263,20 +297,31 @@ impl Board {
             rule50_num
         };
 
-        // updating metadata
-        board.recompute_hash();
-        board.king_sqs = [
-            Square::try_from(board[Piece::King] & board[Color::White])?,
-            Square::try_from(board[Piece::King] & board[Color::Black])?,
+        board.recompute_metadata()?;
+
+        Ok(board)
+    }
+
+    /// Recompute the hash, king squares, checkers, and pinned pieces of a board whose pieces,
+    /// player, castling rights, and en passant square have just been set up.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an `Err` if either side does not have exactly one king, or if
+    /// the resulting board is not valid.
+    fn recompute_metadata(&mut self) -> Result<(), &'static str> {
+        self.recompute_hash();
+        self.king_sqs = [
+            Square::try_from(self[Piece::King] & self[Color::White])?,
+            Square::try_from(self[Piece::King] & self[Color::Black])?,
         ];
-        board.checkers =
-            square_attackers(&board, board.king_sqs[board.player as usize], !board.player);
-        board.recompute_pinned();
-        if !(board.is_valid()) {
+        self.checkers = square_attackers(self, self.king_sqs[self.player as usize], !self.player);
+        self.recompute_pinned();
+        if !(self.is_valid()) {
             return Err("board state after loading was illegal");
         }
 
-        Ok(board)
+        Ok(())
     }
 
     #[inline(always)]
@@ -296,6 +341,150 @@ impl Board {
         self[Color::White] | self[Color::Black]
     }
 
+    #[inline(always)]
+    #[must_use]
+    /// Get the squares occupied by all pieces of the given color.
+    /// Equivalent to `self[color]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Board, Color};
+    ///
+    /// let board = Board::new();
+    /// assert_eq!(board.pieces(Color::White), Bitboard::new(0xFFFF));
+    /// ```
+    pub fn pieces(&self, color: Color) -> Bitboard {
+        self[color]
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Get the squares occupied by pieces of the given color and type.
+    /// Equivalent to `self[pt] & self[color]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Board, Color, Piece};
+    ///
+    /// let board = Board::new();
+    /// assert_eq!(
+    ///     board.pieces_of_type(Color::Black, Piece::Queen),
+    ///     Bitboard::new(0x0800_0000_0000_0000)
+    /// );
+    /// ```
+    pub fn pieces_of_type(&self, color: Color, pt: Piece) -> Bitboard {
+        self[pt] & self[color]
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Get a Zobrist hash of only the pawns on this board.
+    /// Two boards with the same pawn placement have the same pawn hash, no matter where their
+    /// other pieces are, which makes it a suitable key for caching pawn structure evaluation.
+    /// Like the full hash, it is updated incrementally as moves are made.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::Board;
+    ///
+    /// // the knights have moved, but not the pawns
+    /// let b = Board::from_fen("r1bqkb1r/pppppppp/2n2n2/8/8/2N2N2/PPPPPPPP/R1BQKB1R w KQkq - 4 3")
+    ///     .unwrap();
+    /// assert_eq!(b.pawn_hash(), Board::new().pawn_hash());
+    /// ```
+    pub fn pawn_hash(&self) -> u64 {
+        self.pawn_hash
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Get the number of plies which have passed since the last capture or pawn push.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::Board;
+    ///
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 17 40").unwrap();
+    /// assert_eq!(board.rule50(), 17);
+    /// ```
+    pub const fn rule50(&self) -> u8 {
+        self.rule50
+    }
+
+    #[must_use]
+    /// Get every piece, of either color, which attacks `sq` when the occupied squares of the board
+    /// are `occupancy`.
+    /// Passing an occupancy with some pieces removed reveals the sliders behind them, which is how
+    /// x-ray attackers are found.
+    /// Pieces which are not in `occupancy` are never returned as attackers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Board, Square};
+    ///
+    /// let b = Board::new();
+    /// assert_eq!(
+    ///     b.attackers_to(Square::F3, b.occupancy()),
+    ///     Bitboard::from_squares(&[Square::E2, Square::G2, Square::G1])
+    /// );
+    /// ```
+    pub fn attackers_to(&self, sq: Square, occupancy: Bitboard) -> Bitboard {
+        let pawns = self[Piece::Pawn];
+        let rook_sliders = self[Piece::Rook] | self[Piece::Queen];
+        let bishop_sliders = self[Piece::Bishop] | self[Piece::Queen];
+
+        // a white pawn attacks `sq` from the squares a black pawn on `sq` would attack, and vice
+        // versa
+        let attackers =
+            (PAWN_ATTACKS[Color::Black as usize][sq as usize] & pawns & self[Color::White])
+                | (PAWN_ATTACKS[Color::White as usize][sq as usize] & pawns & self[Color::Black])
+                | (KNIGHT_MOVES[sq as usize] & self[Piece::Knight])
+                | (KING_MOVES[sq as usize] & self[Piece::King])
+                | (MAGIC.rook_attacks(occupancy, sq) & rook_sliders)
+                | (MAGIC.bishop_attacks(occupancy, sq) & bishop_sliders);
+
+        attackers & occupancy
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Get the squares of the pieces giving check to the king of the player to move.
+    /// The checkers are recomputed whenever a move is made, so this is free to call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Board, Square};
+    ///
+    /// // Scholar's mate, but White played Qxf7+ without the bishop's support
+    /// let b = Board::from_fen("r1bqkbnr/pppp1Qpp/2n5/4p3/4P3/8/PPPP1PPP/RNB1KBNR b KQkq - 0 3")
+    ///     .unwrap();
+    /// assert_eq!(b.checkers(), Bitboard::from(Square::F7));
+    /// ```
+    pub const fn checkers(&self) -> Bitboard {
+        self.checkers
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Determine whether the player to move is in check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::Board;
+    ///
+    /// assert!(!Board::new().in_check());
+    /// ```
+    pub const fn in_check(&self) -> bool {
+        !self.checkers.is_empty()
+    }
+
     #[inline(always)]
     #[must_use]
     /// Get the type of the piece occupying a given square.
@@ -364,6 +553,129 @@ impl Board {
         self.occupancy().contains(m.to_square()) || m.is_en_passant()
     }
 
+    #[must_use]
+    /// Get the squares that the rook moves from and to when the king makes the castling move `m`.
+    /// The rook's origin is the file recorded for the matching castling right when the board was
+    /// set up, so in Chess960 it may be any file on that side of the king.
+    /// The rook always ends on the F file for kingside castling and the D file for queenside
+    /// castling, as in Chess960.
+    /// In standard chess, this is always a corner rook.
+    ///
+    /// Returns `None` if `m` is not a castle, or if the player to move has no rook on the square
+    /// the castling right refers to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Board, Move, Square};
+    ///
+    /// let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    /// assert_eq!(
+    ///     board.castle_rook_squares(Move::castling(Square::E1, Square::G1)),
+    ///     Some((Square::H1, Square::F1)),
+    /// );
+    /// assert_eq!(
+    ///     board.castle_rook_squares(Move::normal(Square::E1, Square::F1)),
+    ///     None
+    /// );
+    /// ```
+    pub fn castle_rook_squares(&self, m: Move) -> Option<(Square, Square)> {
+        if !m.is_castle() {
+            return None;
+        }
+        let rank = m.from_square().rank();
+        // the king always lands on the G file when castling kingside
+        let kingside = m.to_square().file() == 6;
+        let i = 2 * self.player as usize + usize::from(!kingside);
+        let rook_from_sq = Square::new(rank, self.castle_rook_files[i])?;
+        if !(self[Piece::Rook] & self[self.player]).contains(rook_from_sq) {
+            return None;
+        }
+        let rook_to_sq = Square::new(rank, if kingside { 5 } else { 3 })?;
+
+        Some((rook_from_sq, rook_to_sq))
+    }
+
+    /// Get the castling rights whose rook starts on `sq`.
+    /// These rights are lost once anything moves from or to `sq`.
+    fn castle_rights_of_rook_square(&self, sq: Square) -> CastleRights {
+        let mut rights = CastleRights::NONE;
+        for (i, &file) in self.castle_rook_files.iter().enumerate() {
+            let rank = if i < 2 { 0 } else { 7 };
+            if sq.rank() == rank && sq.file() == file {
+                rights |= CastleRights(1 << i);
+            }
+        }
+
+        rights
+    }
+
+    /// Get the square of the king of `color`, if it stands on its own back rank.
+    fn back_rank_king(&self, color: Color) -> Option<Square> {
+        let back_rank = match color {
+            Color::White => 0,
+            Color::Black => 7,
+        };
+        (self[Piece::King] & self[color] & Bitboard::RANKS[back_rank]).lsb()
+    }
+
+    /// Get the file of the outermost rook of `color` on its back rank, on the kingside or the
+    /// queenside of its king.
+    fn outermost_rook_file(&self, color: Color, kingside: bool) -> Option<u8> {
+        let king_sq = self.back_rank_king(color)?;
+        let back_rooks = self[Piece::Rook] & self[color] & Bitboard::RANKS[king_sq.rank() as usize];
+        let mut files = back_rooks.into_iter().map(Square::file);
+        if kingside {
+            files.filter(|&file| file > king_sq.file()).last()
+        } else {
+            files.find(|&file| file < king_sq.file())
+        }
+    }
+
+    #[must_use]
+    /// Determine whether the square `sq` is attacked by any piece of the color `by`.
+    /// Squares which are threatened only by pawn pushes are not considered attacked.
+    ///
+    /// This is cheaper than computing the full set of attackers, since it stops as soon as a single
+    /// attacker is found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Board, Color, Square};
+    ///
+    /// let board = Board::new();
+    /// assert!(board.is_square_attacked(Square::F3, Color::White));
+    /// assert!(!board.is_square_attacked(Square::F3, Color::Black));
+    /// ```
+    pub fn is_square_attacked(&self, sq: Square, by: Color) -> bool {
+        let attackers = self[by];
+        let sq_idx = sq as usize;
+
+        // cheap lookups first
+        if !(PAWN_ATTACKS[!by as usize][sq_idx] & self[Piece::Pawn] & attackers).is_empty() {
+            return true;
+        }
+        if !(KNIGHT_MOVES[sq_idx] & self[Piece::Knight] & attackers).is_empty() {
+            return true;
+        }
+        if !(KING_MOVES[sq_idx] & self[Piece::King] & attackers).is_empty() {
+            return true;
+        }
+
+        // sliders require a magic lookup
+        let occupancy = self.occupancy();
+        let queens = self[Piece::Queen];
+        if !(MAGIC.rook_attacks(occupancy, sq) & (self[Piece::Rook] | queens) & attackers)
+            .is_empty()
+        {
+            return true;
+        }
+
+        !(MAGIC.bishop_attacks(occupancy, sq) & (self[Piece::Bishop] | queens) & attackers)
+            .is_empty()
+    }
+
     /// Check if the state of this board is valid.
     /// Returns false if the board is invalid.
     fn is_valid(&self) -> bool {
@@ -387,7 +699,7 @@ impl Board {
             return false;
         }
 
-        if self.hash != self.get_fresh_hash() {
+        if self.hash != self.get_fresh_hash() || self.pawn_hash != self.get_fresh_pawn_hash() {
             return false;
         }
 
@@ -447,17 +759,28 @@ impl Board {
         let is_king_move = mover_type == Piece::King;
 
         /* Core move functionality */
-        let capturee = self.type_at_square(to_sq);
+        // in Chess960, the king may castle onto its own rook's square, so lift the rook first
+        let castle_rook = self.castle_rook_squares(m);
+        if let Some((rook_from_sq, _)) = castle_rook {
+            self.remove_known_piece(rook_from_sq, Piece::Rook, player);
+        }
+        // castling never captures, even when the king stays on its own square
+        let capturee = if m.is_castle() {
+            None
+        } else {
+            self.type_at_square(to_sq)
+        };
         if let Some(c) = capturee {
             self.remove_known_piece(to_sq, c, opponent);
         }
         /* Promotion and normal piece movement */
+        // lift the mover before placing it, since a castling king may not move at all
+        self.remove_known_piece(from_sq, mover_type, player);
         if let Some(p) = m.promote_type() {
             self.add_piece(to_sq, p, self.player);
         } else {
             self.add_piece(to_sq, mover_type, self.player);
         }
-        self.remove_known_piece(from_sq, mover_type, player);
 
         /* En passant handling */
         // perform an en passant capture
@@ -490,49 +813,22 @@ impl Board {
         // insert new EP key into hash
 
         /* Handling castling and castle rights */
-        // in normal castling, we describe it with a `Move` as a king move which
-        // jumps two or three squares.
-
-        let mut rights_to_remove;
-        if is_king_move {
-            rights_to_remove = match player {
+        // castling is marked on the move itself, since in Chess960 the king may move only one
+        // file, or not at all
+        let rights_to_remove = if is_king_move {
+            if let Some((_, rook_to_sq)) = castle_rook {
+                self.add_piece(rook_to_sq, Piece::Rook, player);
+            }
+            match player {
                 Color::White => CastleRights::WHITE,
                 Color::Black => CastleRights::BLACK,
-            };
-            if from_sq.file_distance(to_sq) > 1 {
-                // a long move from a king means this must be a castle
-                // G file is file 6 (TODO move this to be a constant?)
-                let is_kingside_castle = to_sq.file() == 6;
-                let (rook_from_file, rook_to_file) = if is_kingside_castle {
-                    (7, 5) // rook moves from H file for kingside castling
-                } else {
-                    (0, 3) // rook moves from A to D for queenside caslting
-                };
-                let rook_from_sq = Square::new(from_sq.rank(), rook_from_file).unwrap();
-                let rook_to_sq = Square::new(from_sq.rank(), rook_to_file).unwrap();
-                self.remove_known_piece(rook_from_sq, Piece::Rook, player);
-                self.add_piece(rook_to_sq, Piece::Rook, self.player);
             }
         } else {
             // don't need to check if it's a rook because moving from this square
             // would mean you didn't have the right anyway
-            rights_to_remove = match from_sq {
-                Square::A1 => CastleRights::WHITE_QUEENSIDE,
-                Square::H1 => CastleRights::WHITE_KINGSIDE,
-                Square::A8 => CastleRights::BLACK_QUEENSIDE,
-                Square::H8 => CastleRights::BLACK_KINGSIDE,
-                _ => CastleRights::NONE,
-            };
-
             // capturing a rook also removes rights
-            rights_to_remove |= match to_sq {
-                Square::A1 => CastleRights::WHITE_QUEENSIDE,
-                Square::H1 => CastleRights::WHITE_KINGSIDE,
-                Square::A8 => CastleRights::BLACK_QUEENSIDE,
-                Square::H8 => CastleRights::BLACK_KINGSIDE,
-                _ => CastleRights::NONE,
-            }
-        }
+            self.castle_rights_of_rook_square(from_sq) | self.castle_rights_of_rook_square(to_sq)
+        };
         self.remove_castle_rights(rights_to_remove);
 
         /* Updating player to move */
@@ -562,13 +858,53 @@ impl Board {
         self.recompute_pinned();
     }
 
+    /// Pass the turn to the opponent without moving a piece, as is done for null-move pruning.
+    /// The en passant square is cleared, the 50-move counter is incremented, and the hash is
+    /// updated to match.
+    ///
+    /// A null move is never legal in chess, and making one while in check would leave the player to
+    /// move able to capture the king.
+    /// It is up to the caller to only make a null move when the player to move is not in check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Board, Color};
+    ///
+    /// let mut board = Board::new();
+    /// board.make_null_move();
+    /// assert_eq!(board.player, Color::Black);
+    /// assert_eq!(
+    ///     board,
+    ///     Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 1 1").unwrap()
+    /// );
+    /// ```
+    pub fn make_null_move(&mut self) {
+        if let Some(ep_sq) = self.en_passant_square {
+            self.hash ^= zobrist::ep_key(ep_sq);
+        }
+        self.en_passant_square = None;
+
+        self.player = !self.player;
+        self.hash ^= zobrist::BLACK_TO_MOVE_KEY;
+        self.rule50 = self.rule50.saturating_add(1);
+
+        // since the previous player was not in check, their opponent cannot be either
+        self.checkers = Bitboard::EMPTY;
+        self.recompute_pinned();
+    }
+
     #[inline(always)]
     /// Remove a piece of a known type at a square.
     /// Will break the validity of the board if there is no piece of type `pt`
     /// and color `color` at `sq`.
     fn remove_known_piece(&mut self, sq: Square, pt: Piece, color: Color) {
         let mask = Bitboard::from(sq);
-        self.hash ^= zobrist::square_key(sq, Some(pt), color);
+        let key = zobrist::square_key(sq, Some(pt), color);
+        self.hash ^= key;
+        if pt == Piece::Pawn {
+            self.pawn_hash ^= key;
+        }
         let removal_mask = !mask;
         self.pieces[pt as usize] &= removal_mask;
         self.sides[color as usize] &= removal_mask;
@@ -586,7 +922,11 @@ impl Board {
         self.pieces[pt as usize] |= mask;
         self.sides[color as usize] |= mask;
         // Update the hash with the result of our addition
-        self.hash ^= zobrist::square_key(sq, Some(pt), color);
+        let key = zobrist::square_key(sq, Some(pt), color);
+        self.hash ^= key;
+        if pt == Piece::Pawn {
+            self.pawn_hash ^= key;
+        }
     }
 
     /// Remove the given `CastleRights` from this board's castling rights, and
@@ -609,27 +949,100 @@ impl Board {
     /// value.
     fn recompute_hash(&mut self) {
         self.hash = self.get_fresh_hash();
+        self.pawn_hash = self.get_fresh_pawn_hash();
     }
 
     /// Recompute the `pinned` metadata of this board.
     fn recompute_pinned(&mut self) {
         self.pinned = Bitboard::EMPTY;
         let king_sq = self.king_sqs[self.player as usize];
+        let occupancy = self.occupancy();
+
+        for sniper_sq in self.snipers(self.player) {
+            let between_bb = Bitboard::between(king_sq, sniper_sq);
+            if (between_bb & occupancy).has_single_bit() {
+                self.pinned |= between_bb;
+            }
+        }
+    }
+
+    /// Get the enemy sliders which are aligned with the king of `color`, and would attack it if the
+    /// board were empty.
+    fn snipers(&self, color: Color) -> Bitboard {
+        let king_sq = self.king_sqs[color as usize];
         let rook_mask = MAGIC.rook_attacks(Bitboard::EMPTY, king_sq);
         let bishop_mask = MAGIC.bishop_attacks(Bitboard::EMPTY, king_sq);
-        let occupancy = self.occupancy();
         let queens = self[Piece::Queen];
 
-        let snipers = self[!self.player]
+        self[!color]
             & ((rook_mask & (queens | self[Piece::Rook]))
-                | (bishop_mask & (queens | self[Piece::Bishop])));
+                | (bishop_mask & (queens | self[Piece::Bishop])))
+    }
 
-        for sniper_sq in snipers {
-            let between_bb = Bitboard::between(king_sq, sniper_sq);
-            if (between_bb & occupancy).has_single_bit() {
-                self.pinned |= between_bb;
+    #[must_use]
+    /// Get the pieces of `color` which are absolutely pinned to their own king by an enemy slider.
+    /// Unlike the `pinned` field, this works for either color, and only contains the pinned pieces
+    /// themselves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Board, Color, Square};
+    ///
+    /// // the bishop on b5 pins the knight on d3 to the king on f1
+    /// let b = Board::from_fen("4k3/8/8/1b6/8/3N4/8/5K2 w - - 0 1").unwrap();
+    /// assert_eq!(b.pinned(Color::White), Bitboard::from(Square::D3));
+    /// assert_eq!(b.pinned(Color::Black), Bitboard::EMPTY);
+    /// ```
+    pub fn pinned(&self, color: Color) -> Bitboard {
+        let king_sq = self.king_sqs[color as usize];
+        let occupancy = self.occupancy();
+        let mut pinned = Bitboard::EMPTY;
+
+        for sniper_sq in self.snipers(color) {
+            let blockers = Bitboard::between(king_sq, sniper_sq) & occupancy;
+            if blockers.has_single_bit() {
+                pinned |= blockers & self[color];
             }
         }
+
+        pinned
+    }
+
+    #[must_use]
+    /// Get the ray along which the piece on `sq` is pinned to its own king: the squares between the
+    /// king and the pinning piece, along with the pinning piece itself.
+    /// A pinned piece can only move to squares on this ray.
+    /// Returns `None` if there is no piece on `sq` or if it is not pinned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Bitboard, Board, Square};
+    ///
+    /// let b = Board::from_fen("4k3/8/8/1b6/8/3N4/8/5K2 w - - 0 1").unwrap();
+    /// assert_eq!(
+    ///     b.pin_ray(Square::D3),
+    ///     Some(Bitboard::from_squares(&[Square::B5, Square::C4, Square::D3, Square::E2]))
+    /// );
+    /// assert_eq!(b.pin_ray(Square::E8), None);
+    /// ```
+    pub fn pin_ray(&self, sq: Square) -> Option<Bitboard> {
+        let color = if self[Color::White].contains(sq) {
+            Color::White
+        } else if self[Color::Black].contains(sq) {
+            Color::Black
+        } else {
+            return None;
+        };
+        let king_sq = self.king_sqs[color as usize];
+        let occupancy = self.occupancy();
+
+        self.snipers(color).into_iter().find_map(|sniper_sq| {
+            let between_bb = Bitboard::between(king_sq, sniper_sq);
+            (between_bb & occupancy == Bitboard::from(sq))
+                .then(|| between_bb | Bitboard::from(sniper_sq))
+        })
     }
 
     #[must_use]
@@ -677,6 +1090,150 @@ impl Board {
         }
     }
 
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    /// Get the color-flipped version of this board: every piece changes color and is mirrored
+    /// vertically, and the player to move, castling rights, and en passant square are swapped to
+    /// match.
+    /// A correct evaluation of the flipped board, from the point of view of the player to move, is
+    /// always the same as that of the original board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Board, Move, Square};
+    ///
+    /// let mut b = Board::new();
+    /// b.make_move(Move::normal(Square::E2, Square::E4));
+    /// let expected =
+    ///     Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    /// assert_eq!(b.color_flip(), expected);
+    /// assert_eq!(b.color_flip().color_flip(), b);
+    /// ```
+    pub fn color_flip(&self) -> Board {
+        let mut flipped = *self;
+        flipped.sides = [
+            self[Color::Black].flip_vertical(),
+            self[Color::White].flip_vertical(),
+        ];
+        for pt in Piece::ALL {
+            flipped.pieces[pt as usize] = self[pt].flip_vertical();
+        }
+        flipped.player = !self.player;
+        flipped.en_passant_square = self.en_passant_square.map(Square::opposite);
+        let rights = self.castle_rights.0;
+        flipped.castle_rights = CastleRights(((rights & 0x3) << 2) | (rights >> 2));
+        let files = self.castle_rook_files;
+        flipped.castle_rook_files = [files[2], files[3], files[0], files[1]];
+
+        // a mirror image of a valid board is always valid
+        flipped.recompute_metadata().unwrap();
+
+        flipped
+    }
+
+    #[must_use]
+    /// Render this board as a labeled grid, for debugging.
+    /// The grid has rank 8 at the top and the A-file on the left, with White pieces in uppercase
+    /// and Black pieces in lowercase.
+    /// Below the grid is a footer line giving the side to move, the castling rights, and the en
+    /// passant square, in the same notation as a FEN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::Board;
+    ///
+    /// let pretty = Board::new().pretty();
+    /// assert!(pretty.starts_with("8 r n b q k b n r\n"));
+    /// assert!(pretty.ends_with("w KQkq -\n"));
+    /// ```
+    pub fn pretty(&self) -> String {
+        let mut s = self.grid('.', |pt, color| match color {
+            Color::White => pt.code(),
+            Color::Black => pt.code().to_ascii_lowercase(),
+        });
+
+        s.push(match self.player {
+            Color::White => 'w',
+            Color::Black => 'b',
+        });
+        s.push(' ');
+        let rights = [
+            (self.castle_rights.kingside(Color::White), 'K'),
+            (self.castle_rights.queenside(Color::White), 'Q'),
+            (self.castle_rights.kingside(Color::Black), 'k'),
+            (self.castle_rights.queenside(Color::Black), 'q'),
+        ];
+        if rights.iter().any(|&(has, _)| has) {
+            s.extend(rights.iter().filter(|&&(has, _)| has).map(|&(_, c)| c));
+        } else {
+            s.push('-');
+        }
+        s.push(' ');
+        match self.en_passant_square {
+            Some(ep_sq) => s.push_str(&ep_sq.to_string()),
+            None => s.push('-'),
+        }
+        s.push('\n');
+
+        s
+    }
+
+    #[must_use]
+    /// Render this board as a labeled grid of Unicode chess glyphs, for debugging.
+    /// The grid has the same layout as `pretty()`, with empty squares shown as dots, but has no
+    /// footer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::Board;
+    ///
+    /// let unicode = Board::new().to_unicode();
+    /// assert!(unicode.starts_with("8 ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜\n"));
+    /// ```
+    pub fn to_unicode(&self) -> String {
+        self.grid('·', Piece::glyph)
+    }
+
+    /// Render the pieces of this board as a grid with rank and file labels, drawing each piece
+    /// with `glyph` and each empty square with `empty`.
+    fn grid(&self, empty: char, glyph: impl Fn(Piece, Color) -> char) -> String {
+        let mut grid = [empty; 64];
+        for pt in Piece::ALL {
+            for color in [Color::White, Color::Black] {
+                for sq in self[pt] & self[color] {
+                    grid[sq as usize] = glyph(pt, color);
+                }
+            }
+        }
+
+        let mut s = String::new();
+        for rank in (0..8).rev() {
+            s.push(char::from(b'1' + rank));
+            for &c in &grid[8 * rank as usize..8 * (rank as usize + 1)] {
+                s.push(' ');
+                s.push(c);
+            }
+            s.push('\n');
+        }
+        s.push_str("  a b c d e f g h\n");
+
+        s
+    }
+
+    /// Compute the pawn hash of this board from scratch.
+    fn get_fresh_pawn_hash(&self) -> u64 {
+        let mut hash = 0;
+        for color in [Color::White, Color::Black] {
+            for sq in self[Piece::Pawn] & self[color] {
+                hash ^= zobrist::square_key(sq, Some(Piece::Pawn), color);
+            }
+        }
+        hash
+    }
+
     /// Compute the hash value of this board from scratch. This should
     /// generally only be used for debug purposes, as in most cases iteratively
     /// updating the hashes as moves are made is enough.
@@ -744,6 +1301,11 @@ impl PartialEq for Board {
             && self.en_passant_square == other.en_passant_square
             && self.player == other.player
             && self.castle_rights == other.castle_rights
+            // the rook files only matter for the rights which are still held
+            && (0..4).all(|i| {
+                self.castle_rights.0 & (1 << i) == 0
+                    || self.castle_rook_files[i] == other.castle_rook_files[i]
+            })
     }
 }
 
@@ -777,6 +1339,204 @@ impl Default for Board {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A builder for setting up a [`Board`] piece by piece, without writing a FEN.
+/// The builder starts with an empty board, White to move, no castling rights, and no en passant
+/// square.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, BoardBuilder, Color, Piece, Square};
+///
+/// let b = BoardBuilder::new()
+///     .piece(Square::E1, Color::White, Piece::King)
+///     .piece(Square::E2, Color::White, Piece::Pawn)
+///     .piece(Square::E8, Color::Black, Piece::King)
+///     .build()
+///     .unwrap();
+/// assert_eq!(b, Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap());
+/// ```
+pub struct BoardBuilder {
+    /// The squares occupied by White and Black, respectively.
+    sides: [Bitboard; 2],
+    /// The squares occupied by each type of piece.
+    pieces: [Bitboard; Piece::NUM],
+    /// The color of the player to move.
+    player: Color,
+    /// The castling rights of each player.
+    castle_rights: CastleRights,
+    /// The square which can be moved to by a pawn in en passant.
+    en_passant_square: Option<Square>,
+}
+
+impl BoardBuilder {
+    #[must_use]
+    /// Construct a new builder with an empty board.
+    pub fn new() -> BoardBuilder {
+        BoardBuilder {
+            sides: [Bitboard::EMPTY; 2],
+            pieces: [Bitboard::EMPTY; Piece::NUM],
+            player: Color::White,
+            castle_rights: CastleRights::NONE,
+            en_passant_square: None,
+        }
+    }
+
+    #[must_use]
+    /// Place a piece on `sq`, replacing whatever was there before.
+    pub fn piece(mut self, sq: Square, color: Color, pt: Piece) -> BoardBuilder {
+        let mask = Bitboard::from(sq);
+        for bb in self.sides.iter_mut().chain(self.pieces.iter_mut()) {
+            *bb &= !mask;
+        }
+        self.sides[color as usize] |= mask;
+        self.pieces[pt as usize] |= mask;
+        self
+    }
+
+    #[must_use]
+    /// Set the player to move.
+    pub fn player(mut self, color: Color) -> BoardBuilder {
+        self.player = color;
+        self
+    }
+
+    #[must_use]
+    /// Give `color` the right to castle kingside.
+    pub fn castle_kingside(mut self, color: Color) -> BoardBuilder {
+        self.castle_rights |= match color {
+            Color::White => CastleRights::WHITE_KINGSIDE,
+            Color::Black => CastleRights::BLACK_KINGSIDE,
+        };
+        self
+    }
+
+    #[must_use]
+    /// Give `color` the right to castle queenside.
+    pub fn castle_queenside(mut self, color: Color) -> BoardBuilder {
+        self.castle_rights |= match color {
+            Color::White => CastleRights::WHITE_QUEENSIDE,
+            Color::Black => CastleRights::BLACK_QUEENSIDE,
+        };
+        self
+    }
+
+    #[must_use]
+    /// Set the square which a pawn may capture onto en passant.
+    pub fn en_passant(mut self, sq: Square) -> BoardBuilder {
+        self.en_passant_square = Some(sq);
+        self
+    }
+
+    /// Build the board, checking that the position is legal.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an `Err` if either side does not have exactly one king, if there
+    /// are pawns on the first or last rank, if a castling right is given without the king and rook
+    /// on their starting squares, if the en passant square does not follow a double pawn push, or
+    /// if the player who is not to move is in check.
+    pub fn build(&self) -> Result<Board, &'static str> {
+        for color in [Color::White, Color::Black] {
+            if (self.pieces[Piece::King as usize] & self.sides[color as usize]).len() != 1 {
+                return Err("each side must have exactly one king");
+            }
+        }
+
+        let back_ranks = Bitboard::RANKS[0] | Bitboard::RANKS[7];
+        if !(self.pieces[Piece::Pawn as usize] & back_ranks).is_empty() {
+            return Err("pawns cannot be on the first or last rank");
+        }
+
+        let has = |sq: Square, color: Color, pt: Piece| {
+            (self.sides[color as usize] & self.pieces[pt as usize]).contains(sq)
+        };
+        for (right, color, king_sq, rook_sq) in [
+            (
+                CastleRights::WHITE_KINGSIDE,
+                Color::White,
+                Square::E1,
+                Square::H1,
+            ),
+            (
+                CastleRights::WHITE_QUEENSIDE,
+                Color::White,
+                Square::E1,
+                Square::A1,
+            ),
+            (
+                CastleRights::BLACK_KINGSIDE,
+                Color::Black,
+                Square::E8,
+                Square::H8,
+            ),
+            (
+                CastleRights::BLACK_QUEENSIDE,
+                Color::Black,
+                Square::E8,
+                Square::A8,
+            ),
+        ] {
+            if self.castle_rights & right != CastleRights::NONE
+                && !(has(king_sq, color, Piece::King) && has(rook_sq, color, Piece::Rook))
+            {
+                return Err("castling rights require the king and rook on their starting squares");
+            }
+        }
+
+        if let Some(ep_sq) = self.en_passant_square {
+            // the pawn which just moved two squares must be right in front of the en passant
+            // square, and the squares it passed through must be empty
+            let (ep_rank, dr) = match self.player {
+                Color::White => (5, -1),
+                Color::Black => (2, 1),
+            };
+            let occupancy = self.sides[0] | self.sides[1];
+            let valid = ep_sq.rank() == ep_rank
+                && ep_sq
+                    .try_offset(0, dr)
+                    .is_some_and(|sq| has(sq, !self.player, Piece::Pawn))
+                && ep_sq
+                    .try_offset(0, -dr)
+                    .is_some_and(|sq| !occupancy.contains(sq))
+                && !occupancy.contains(ep_sq);
+            if !valid {
+                return Err("en passant square does not follow a double pawn push");
+            }
+        }
+
+        let mut board = Board {
+            sides: self.sides,
+            pieces: self.pieces,
+            en_passant_square: self.en_passant_square,
+            player: self.player,
+            castle_rights: self.castle_rights,
+            castle_rook_files: STANDARD_ROOK_FILES,
+            rule50: 0,
+            hash: 0,
+            pawn_hash: 0,
+            checkers: Bitboard::EMPTY,
+            king_sqs: [Square::A1; 2],
+            pinned: Bitboard::EMPTY,
+        };
+        board.recompute_metadata()?;
+
+        let waiting_king = board.king_sqs[!board.player as usize];
+        if !square_attackers(&board, waiting_king, board.player).is_empty() {
+            return Err("the player who is not to move is in check");
+        }
+
+        Ok(board)
+    }
+}
+
+impl Default for BoardBuilder {
+    fn default() -> BoardBuilder {
+        BoardBuilder::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -906,8 +1666,10 @@ mod tests {
             en_passant_square: None,
             player: Color::White,
             castle_rights: CastleRights::NONE,
+            castle_rook_files: STANDARD_ROOK_FILES,
             rule50: 0,
             hash: 3_483_926_298_739_092_744,
+            pawn_hash: 0,
             checkers: Bitboard::EMPTY,
             king_sqs: [Square::A1, Square::H8],
             pinned: Bitboard::EMPTY,
@@ -930,6 +1692,99 @@ mod tests {
         move_helper(Board::default(), Move::normal(Square::E2, Square::E4));
     }
 
+    #[test]
+    /// Test the occupancy and piece-set accessors on the start position.
+    fn start_piece_sets() {
+        let b = Board::new();
+        assert_eq!(b.occupancy().len(), 32);
+        assert_eq!(b.pieces(Color::White).len(), 16);
+        assert_eq!(b.pieces(Color::Black).len(), 16);
+        assert_eq!(
+            b.pieces_of_type(Color::White, Piece::Pawn),
+            Color::White.pawn_start_rank()
+        );
+        assert_eq!(
+            b.pieces_of_type(Color::White, Piece::King),
+            Bitboard::from(Square::E1)
+        );
+        for pt in Piece::ALL {
+            assert_eq!(
+                b.pieces_of_type(Color::White, pt) | b.pieces_of_type(Color::Black, pt),
+                b[pt]
+            );
+        }
+    }
+
+    #[test]
+    /// Test that color-flipping a board swaps every part of its state, and that flipping twice
+    /// gives back the original board.
+    fn color_flip_state() {
+        let b =
+            Board::from_fen("rnbqkb1r/ppppp1pp/7n/4Pp2/8/8/PPPP1PPP/RNBQKBNR w Kq f6 0 3").unwrap();
+        let expected =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/8/4pP2/7N/PPPPP1PP/RNBQKB1R b Qk f3 0 3").unwrap();
+        let flipped = b.color_flip();
+        assert_eq!(flipped, expected);
+        assert_eq!(flipped.hash, expected.hash);
+        assert_eq!(flipped.king_sqs, expected.king_sqs);
+        assert_eq!(flipped.color_flip(), b);
+
+        // metadata such as checkers and pins are recomputed for the new player
+        let pinned = Board::from_fen("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            pinned.color_flip().pinned(Color::Black),
+            Bitboard::from(Square::E7)
+        );
+        let check = Board::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+        assert_eq!(check.color_flip().checkers, Bitboard::from(Square::H8));
+    }
+
+    #[test]
+    /// Test that the start position renders to the expected labeled grid.
+    fn pretty_start() {
+        let expected = "\
+8 r n b q k b n r
+7 p p p p p p p p
+6 . . . . . . . .
+5 . . . . . . . .
+4 . . . . . . . .
+3 . . . . . . . .
+2 P P P P P P P P
+1 R N B Q K B N R
+  a b c d e f g h
+w KQkq -
+";
+        assert_eq!(Board::new().pretty(), expected);
+    }
+
+    #[test]
+    /// Test that the start position renders to the expected grid of Unicode glyphs.
+    fn unicode_start() {
+        let expected = "\
+8 ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜
+7 ♟ ♟ ♟ ♟ ♟ ♟ ♟ ♟
+6 · · · · · · · ·
+5 · · · · · · · ·
+4 · · · · · · · ·
+3 · · · · · · · ·
+2 ♙ ♙ ♙ ♙ ♙ ♙ ♙ ♙
+1 ♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖
+  a b c d e f g h
+";
+        assert_eq!(Board::new().to_unicode(), expected);
+    }
+
+    #[test]
+    /// Test that the footer of a pretty-printed board reflects the side to move, partial castling
+    /// rights, and the en passant square.
+    fn pretty_footer() {
+        let b =
+            Board::from_fen("rnbqkb1r/ppppp1pp/7n/4Pp2/8/8/PPPP1PPP/RNBQKBNR w Kq f6 0 3").unwrap();
+        assert!(b.pretty().ends_with("\nw Kq f6\n"));
+        let b = Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+        assert!(b.pretty().ends_with("\nb - -\n"));
+    }
+
     #[test]
     /// Test that a board with an en passant square can be loaded from a FEN
     /// correctly.
@@ -955,7 +1810,7 @@ mod tests {
     fn white_kingide_castle() {
         fen_helper(
             "r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
-            Move::normal(Square::E1, Square::G1),
+            Move::castling(Square::E1, Square::G1),
         );
     }
 
@@ -980,6 +1835,54 @@ mod tests {
         );
     }
 
+    #[test]
+    /// Test that White attacks nothing past its own half in the start position.
+    fn start_no_far_attacks() {
+        let b = Board::default();
+        for sq in Bitboard::new(0xFFFF_FFFF_0000_0000) {
+            assert!(!b.is_square_attacked(sq, Color::White));
+        }
+    }
+
+    #[test]
+    /// Test that the e-file squares next to the kings are attacked in the start position.
+    fn start_king_neighbors_attacked() {
+        let b = Board::default();
+        assert!(b.is_square_attacked(Square::E2, Color::White));
+        assert!(b.is_square_attacked(Square::E7, Color::Black));
+        assert!(!b.is_square_attacked(Square::E2, Color::Black));
+        assert!(!b.is_square_attacked(Square::E7, Color::White));
+    }
+
+    #[test]
+    /// Test that a lone king attacks exactly its eight neighbors.
+    fn lone_king_attacks() {
+        let b = Board::from_fen("k7/8/8/8/4K3/8/8/8 w - - 0 1").unwrap();
+        let neighbors = KING_MOVES[Square::E4 as usize];
+        assert_eq!(neighbors.len(), 8);
+        for sq in Bitboard::ALL {
+            assert_eq!(
+                b.is_square_attacked(sq, Color::White),
+                neighbors.contains(sq)
+            );
+        }
+    }
+
+    #[test]
+    /// Test that `is_square_attacked` agrees with the full attacker computation.
+    fn attacked_matches_attackers() {
+        let b =
+            Board::from_fen("r1bq1b1r/ppp2kpp/2n5/3np3/2B5/8/PPPP1PPP/RNBQK2R w KQ - 0 7").unwrap();
+        for sq in Bitboard::ALL {
+            for color in [Color::White, Color::Black] {
+                assert_eq!(
+                    b.is_square_attacked(sq, color),
+                    !square_attackers(&b, sq, color).is_empty()
+                );
+            }
+        }
+    }
+
     /// Tests regarding drawn positions.
     mod draws {
         use super::*;
@@ -1021,4 +1924,207 @@ mod tests {
             draw_helper("rnbqk2r/ppppnp1p/4p1pb/8/4P3/1P1P4/PBP2PPP/RN1QKBNR w KQkq - 100 100");
         }
     }
+
+    #[test]
+    /// Test that a simple endgame built piece by piece matches the same position parsed from FEN.
+    fn builder_endgame() {
+        let built = BoardBuilder::new()
+            .piece(Square::G1, Color::White, Piece::King)
+            .piece(Square::D1, Color::White, Piece::Rook)
+            .piece(Square::F2, Color::White, Piece::Pawn)
+            .piece(Square::G7, Color::Black, Piece::King)
+            .piece(Square::A4, Color::Black, Piece::Pawn)
+            .player(Color::Black)
+            .build()
+            .unwrap();
+        let parsed = Board::from_fen("8/6k1/8/8/p7/8/5P2/3R2K1 b - - 0 1").unwrap();
+        assert_eq!(built, parsed);
+        assert_eq!(built.hash, parsed.hash);
+        assert_eq!(built.king_sqs, parsed.king_sqs);
+    }
+
+    #[test]
+    /// Test that castling rights and en passant squares carry over from the builder.
+    fn builder_castle_en_passant() {
+        let built = BoardBuilder::new()
+            .piece(Square::E1, Color::White, Piece::King)
+            .piece(Square::H1, Color::White, Piece::Rook)
+            .piece(Square::E5, Color::White, Piece::Pawn)
+            .piece(Square::E8, Color::Black, Piece::King)
+            .piece(Square::A8, Color::Black, Piece::Rook)
+            .piece(Square::D5, Color::Black, Piece::Pawn)
+            .castle_kingside(Color::White)
+            .castle_queenside(Color::Black)
+            .en_passant(Square::D6)
+            .build()
+            .unwrap();
+        let parsed = Board::from_fen("r3k3/8/8/3pP3/8/8/8/4K2R w Kq d6 0 1").unwrap();
+        assert_eq!(built, parsed);
+        assert_eq!(built.hash, parsed.hash);
+    }
+
+    #[test]
+    /// Test that the builder rejects illegal positions.
+    fn builder_illegal() {
+        let kings = BoardBuilder::new()
+            .piece(Square::E1, Color::White, Piece::King)
+            .piece(Square::E8, Color::Black, Piece::King);
+        assert!(kings.build().is_ok());
+
+        // missing or extra kings
+        assert!(BoardBuilder::new().build().is_err());
+        assert!(kings
+            .piece(Square::A1, Color::White, Piece::King)
+            .build()
+            .is_err());
+        // replacing a king leaves its side without one
+        assert!(kings
+            .piece(Square::E1, Color::White, Piece::Queen)
+            .build()
+            .is_err());
+
+        // pawns on the back ranks
+        assert!(kings
+            .piece(Square::A8, Color::White, Piece::Pawn)
+            .build()
+            .is_err());
+        assert!(kings
+            .piece(Square::A1, Color::Black, Piece::Pawn)
+            .build()
+            .is_err());
+
+        // castling without a rook
+        assert!(kings.castle_kingside(Color::White).build().is_err());
+
+        // en passant with no pawn to capture
+        assert!(kings.en_passant(Square::D6).build().is_err());
+
+        // the player not to move is in check
+        assert!(kings
+            .piece(Square::E4, Color::White, Piece::Rook)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    /// Test check detection in a quiet position, a single check, and a double check, and that the
+    /// checkers agree with a fresh computation of the king's attackers.
+    fn checkers_and_in_check() {
+        let quiet =
+            Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")
+                .unwrap();
+        assert!(!quiet.in_check());
+        assert_eq!(quiet.checkers(), Bitboard::EMPTY);
+
+        let single =
+            Board::from_fen("rnbqkbnr/ppp2ppp/3p4/1B2p3/4P3/8/PPPP1PPP/RNBQK1NR b KQkq - 1 3")
+                .unwrap();
+        assert!(single.in_check());
+        assert_eq!(single.checkers(), Bitboard::from(Square::B5));
+
+        // the knight on f6 and the rook on e1 both give check
+        let double = Board::from_fen("4k3/8/5N2/8/8/8/8/4R1K1 b - - 0 1").unwrap();
+        assert!(double.in_check());
+        assert_eq!(
+            double.checkers(),
+            Bitboard::from_squares(&[Square::F6, Square::E1])
+        );
+
+        for b in [quiet, single, double] {
+            let king_sq = b.king_sqs[b.player as usize];
+            assert_eq!(b.checkers(), square_attackers(&b, king_sq, !b.player));
+        }
+    }
+
+    #[test]
+    /// Test that a knight pinned to its king by a bishop is detected, while the same knight with
+    /// the king off the diagonal is not.
+    fn knight_pinned_by_bishop() {
+        let pinned = Board::from_fen("4k3/8/8/1b6/8/3N4/8/5K2 w - - 0 1").unwrap();
+        let free = Board::from_fen("4k3/8/8/1b6/8/3N4/8/6K1 w - - 0 1").unwrap();
+        assert!(pinned.pinned(Color::White).contains(Square::D3));
+        assert_eq!(free.pinned(Color::White), Bitboard::EMPTY);
+        assert!(pinned.pin_ray(Square::D3).unwrap().contains(Square::B5));
+        assert_eq!(free.pin_ray(Square::D3), None);
+
+        // with two pieces between the king and the bishop, neither is pinned
+        let shielded = Board::from_fen("4k3/8/8/1b6/2P5/3N4/8/5K2 w - - 0 1").unwrap();
+        assert_eq!(shielded.pinned(Color::White), Bitboard::EMPTY);
+    }
+
+    #[test]
+    /// Test that pins are found for the player who is not to move, and that enemy pieces in the
+    /// way are not counted as pinned.
+    fn pinned_either_color() {
+        // the rook on e1 pins the Black queen on e5 to the king on e8
+        let b = Board::from_fen("4k3/8/8/4q3/8/8/8/4R1K1 w - - 0 1").unwrap();
+        assert_eq!(b.pinned(Color::Black), Bitboard::from(Square::E5));
+        assert_eq!(b.pinned(Color::White), Bitboard::EMPTY);
+        assert_eq!(
+            b.pin_ray(Square::E5),
+            Some(Bitboard::between(Square::E8, Square::E1) | Bitboard::from(Square::E1))
+        );
+
+        // a White knight between a White rook and the Black king is not pinned, since it is not
+        // between the rook and its own king
+        let b = Board::from_fen("R3N1k1/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(b.pinned(Color::Black), Bitboard::EMPTY);
+        assert_eq!(b.pin_ray(Square::E8), None);
+    }
+
+    #[test]
+    /// Test that every attacker of a crowded square is found, and that removing an occupier reveals
+    /// the x-ray attacker behind it.
+    fn attackers_crowded_square() {
+        // e5 is attacked by the White pawn on d4, knight on f3, and rook on e1, and by the Black
+        // pawn on f6, knight on c6, bishop on b8, and queen on e7.
+        // The Black rook on e8 is lined up behind the queen.
+        let b = Board::from_fen("1b2r1k1/4q3/2n2p2/4p3/3P4/5N2/8/4R1K1 w - - 0 1").unwrap();
+        let occupancy = b.occupancy();
+        let expected = Bitboard::from_squares(&[
+            Square::D4,
+            Square::F3,
+            Square::E1,
+            Square::F6,
+            Square::C6,
+            Square::B8,
+            Square::E7,
+        ]);
+        assert_eq!(b.attackers_to(Square::E5, occupancy), expected);
+
+        // agree with the per-color attacker lookup
+        assert_eq!(
+            b.attackers_to(Square::E5, occupancy) & b[Color::White],
+            square_attackers(&b, Square::E5, Color::White)
+        );
+
+        // once the queen is gone, the rook behind it attacks e5
+        let without_queen = occupancy & !Bitboard::from(Square::E7);
+        let revealed = b.attackers_to(Square::E5, without_queen);
+        assert!(revealed.contains(Square::E8));
+        assert!(!revealed.contains(Square::E7));
+        assert_eq!(
+            revealed,
+            expected & !Bitboard::from(Square::E7) | Bitboard::from(Square::E8)
+        );
+    }
+
+    #[test]
+    /// Test that the pawn hash is updated correctly by pushes, captures, promotions, and en
+    /// passant.
+    fn pawn_hash_incremental() {
+        use crate::base::movegen::{get_moves, GenMode};
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkb1r/ppppp1pp/7n/4Pp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "r4bkr/pPpq2pp/2n1b3/3n4/2BPp3/2P5/1P3PPP/RNBQK2R w KQ - 1 13",
+        ] {
+            let b = Board::from_fen(fen).unwrap();
+            for m in get_moves::<{ GenMode::All }>(&b) {
+                let mut after = b;
+                after.make_move(m);
+                assert_eq!(after.pawn_hash(), after.get_fresh_pawn_hash(), "{fen} {m}");
+            }
+        }
+    }
 }
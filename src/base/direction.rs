@@ -18,8 +18,10 @@
 
 //! Directions, which form a vector field describing motions between `Square`s.
 
+#![warn(clippy::std_instead_of_core, clippy::std_instead_of_alloc)]
+
 use super::Square;
-use std::ops::{Add, Mul, Neg, Sub};
+use core::ops::{Add, Mul, Neg, Sub};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 /// A difference between two squares. `Direction`s form a vector field, which allows us to define
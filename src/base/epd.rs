@@ -0,0 +1,234 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Extended Position Description (EPD) parsing.
+//!
+//! EPD is the format used by test suites such as Win at Chess.
+//! Each line describes a position with the first four fields of a FEN, followed by a list of
+//! operations, each of which is an opcode and its operands, terminated by a semicolon.
+//! For instance, `bm Qg6;` gives the best move in the position, and `id "WAC.001";` names it.
+
+use std::collections::HashMap;
+
+use super::{Board, Move};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A position parsed from an EPD line, along with its operations.
+pub struct Epd {
+    /// The position described by the EPD.
+    pub board: Board,
+    /// The operands of each operation, keyed by opcode.
+    /// Quoted operands are stored without their quotes.
+    pub operations: HashMap<String, Vec<String>>,
+    /// The best moves given by the `bm` operation, resolved against `board`.
+    pub best_moves: Vec<Move>,
+    /// The moves to avoid given by the `am` operation, resolved against `board`.
+    pub avoid_moves: Vec<Move>,
+}
+
+impl Epd {
+    #[must_use]
+    /// Get the name of this position, given by the `id` operation.
+    pub fn id(&self) -> Option<&str> {
+        self.operations
+            .get("id")
+            .and_then(|operands| operands.first())
+            .map(String::as_str)
+    }
+}
+
+/// Parse a single line of EPD.
+/// If the line has an `hmvc` operation, it is used as the halfmove clock of the position.
+/// The moves in the `bm` and `am` operations must be in standard algebraic notation, and must be
+/// legal in the position.
+///
+/// # Errors
+///
+/// This function will return an `Err` if the position is invalid, if an operation is malformed,
+/// or if a move in `bm` or `am` cannot be resolved.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{epd::parse_epd, Move, Square};
+///
+/// let epd = parse_epd(
+///     "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";",
+/// )
+/// .unwrap();
+/// assert_eq!(epd.id(), Some("WAC.001"));
+/// assert_eq!(epd.best_moves, vec![Move::normal(Square::G3, Square::G6)]);
+/// ```
+pub fn parse_epd(line: &str) -> Result<Epd, &'static str> {
+    // fields may be separated by any run of whitespace, but the operations are kept intact since
+    // quoted operands can contain spaces of their own
+    let mut rest = line.trim();
+    let mut position = Vec::with_capacity(4);
+    for _ in 0..4 {
+        if rest.is_empty() {
+            return Err("EPD has fewer than four position fields");
+        }
+        let (field, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        position.push(field);
+        rest = tail.trim_start();
+    }
+    let operations = parse_operations(rest)?;
+
+    let halfmove_clock = match operations.get("hmvc").and_then(|operands| operands.first()) {
+        Some(hmvc) => hmvc.as_str(),
+        None => "0",
+    };
+    let fen = format!("{} {halfmove_clock} 1", position.join(" "));
+    let board = Board::from_fen(&fen)?;
+
+    let resolve = |opcode: &str| -> Result<Vec<Move>, &'static str> {
+        operations
+            .get(opcode)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|san| Move::from_san(&board, san))
+            .collect()
+    };
+    let best_moves = resolve("bm")?;
+    let avoid_moves = resolve("am")?;
+
+    Ok(Epd {
+        board,
+        operations,
+        best_moves,
+        avoid_moves,
+    })
+}
+
+/// Parse the operations section of an EPD line into a map from opcodes to operands.
+fn parse_operations(s: &str) -> Result<HashMap<String, Vec<String>>, &'static str> {
+    let mut operations = HashMap::new();
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                // quoted operands may contain spaces and semicolons
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => token.push(c),
+                        None => return Err("unterminated string operand in EPD"),
+                    }
+                }
+                tokens.push(std::mem::take(&mut token));
+            }
+            ';' => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+                let mut operation = tokens.drain(..);
+                let opcode = operation.next().ok_or("empty operation in EPD")?;
+                operations.insert(opcode, operation.collect());
+            }
+            c if c.is_whitespace() => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+
+    if !token.is_empty() || !tokens.is_empty() {
+        return Err("EPD operation is not terminated by a semicolon");
+    }
+
+    Ok(operations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::Square;
+
+    #[test]
+    /// Test that a few lines from the Win at Chess suite parse, and that their best moves resolve.
+    fn wac_lines() {
+        let epd = parse_epd(
+            "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";",
+        )
+        .unwrap();
+        assert_eq!(epd.id(), Some("WAC.001"));
+        assert_eq!(epd.best_moves, vec![Move::normal(Square::G3, Square::G6)]);
+        assert_eq!(
+            epd.board,
+            Board::from_fen("2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1").unwrap()
+        );
+
+        let epd = parse_epd("8/7p/5k2/5p2/p1p2P2/Pr1pPK2/1P1R3P/8 b - - bm Rxb2; id \"WAC.002\";")
+            .unwrap();
+        assert_eq!(epd.id(), Some("WAC.002"));
+        assert_eq!(epd.best_moves, vec![Move::normal(Square::B3, Square::B2)]);
+        assert!(epd.avoid_moves.is_empty());
+    }
+
+    #[test]
+    /// Test that operations with several operands, quoted semicolons, and a halfmove clock are
+    /// parsed.
+    fn operations() {
+        let epd = parse_epd(
+            "5rk1/1ppb3p/p1pb4/6q1/3P1p1r/2P1R2P/PP1BQ1P1/5RKN w - - \
+             bm Rg3 Ref3; am Qf2; c0 \"a; b\"; hmvc 7; id \"WAC.003\";",
+        )
+        .unwrap();
+        assert_eq!(
+            epd.best_moves,
+            vec![
+                Move::normal(Square::E3, Square::G3),
+                Move::normal(Square::E3, Square::F3)
+            ]
+        );
+        assert_eq!(epd.avoid_moves, vec![Move::normal(Square::E2, Square::F2)]);
+        assert_eq!(epd.operations["c0"], vec!["a; b".to_string()]);
+        assert_eq!(epd.operations["hmvc"], vec!["7".to_string()]);
+        assert_eq!(
+            epd.board,
+            Board::from_fen("5rk1/1ppb3p/p1pb4/6q1/3P1p1r/2P1R2P/PP1BQ1P1/5RKN w - - 7 1").unwrap()
+        );
+    }
+
+    #[test]
+    /// Test that fields separated by more than one space or by tabs still parse, and that spaces
+    /// inside quoted operands are preserved.
+    fn extra_whitespace() {
+        let epd = parse_epd("4k3/8/8/8/8/8/8/4K3  w\t-   -  id \"two  spaces\";").unwrap();
+        assert_eq!(
+            epd.board,
+            Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap()
+        );
+        assert_eq!(epd.id(), Some("two  spaces"));
+    }
+
+    #[test]
+    /// Test that malformed lines and unresolvable best moves are rejected.
+    fn bad_epd() {
+        assert!(parse_epd("8/8/8 w - -").is_err());
+        assert!(parse_epd("4k3/8/8/8/8/8/8/4K3 w - - bm Qg6;").is_err());
+        assert!(parse_epd("4k3/8/8/8/8/8/8/4K3 w - - id \"x\"").is_err());
+        assert!(parse_epd("4k3/8/8/8/8/8/8/4K3 w - - id \"x;").is_err());
+        assert!(parse_epd("4k3/8/8/8/8/8/8/4K3 w - -").is_ok());
+    }
+}
@@ -22,12 +22,31 @@ use super::movegen::is_legal;
 
 use super::{
     movegen::{get_moves, has_moves, GenMode},
-    Board, Move,
+    Board, Color, Move,
 };
 
 use nohash_hasher::IntMap;
 
-use std::default::Default;
+use std::{default::Default, fmt::Write};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Detailed counts from a performance test of the move generator.
+/// All counts except `nodes` describe the moves which were played to reach the leaf positions, so
+/// they can be compared directly against published perft tables.
+pub struct PerftStats {
+    /// The number of leaf positions reached.
+    pub nodes: u64,
+    /// The number of leaf moves which were captures, including en passant.
+    pub captures: u64,
+    /// The number of leaf moves which were en passant captures.
+    pub en_passants: u64,
+    /// The number of leaf moves which were castles.
+    pub castles: u64,
+    /// The number of leaf moves which were promotions.
+    pub promotions: u64,
+    /// The number of leaf moves which gave check.
+    pub checks: u64,
+}
 
 #[allow(clippy::module_name_repetitions)]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -67,7 +86,6 @@ impl Game {
     /// This function will return an `Err` if the FEN string is invalid.
     pub fn from_fen(fen: &str) -> Result<Game, &'static str> {
         let b = Board::from_fen(fen)?;
-        // TODO extract 50 move rule from the FEN
         Ok(Game {
             history: vec![b],
             moves: Vec::new(),
@@ -131,6 +149,12 @@ impl Game {
     /// This function will return `Ok()` if there was history to undo.
     /// The move inside the `Ok` variant will be the most recent move played.
     ///
+    /// Undoing is guaranteed to restore the game to exactly the state it had before the move was
+    /// made, including the castling rights, en passant square, 50-move counter, Zobrist hash, and
+    /// repetition counts.
+    /// Since the history stores full copies of every board, nothing has to be incrementally
+    /// reversed.
+    ///
     /// # Errors
     ///
     /// This function will return an `Err` if the history of this game has no more positions left
@@ -147,6 +171,59 @@ impl Game {
         Ok(m_removed)
     }
 
+    #[allow(clippy::missing_panics_doc)]
+    /// Pass the turn to the opponent without moving, for null-move pruning.
+    /// The null move is recorded in the history as `Move::BAD_MOVE`, and should be taken back with
+    /// `undo_null_move()` (or `undo()`); `to_pgn()` refuses to export a game containing one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an `Err` if the player to move is in check, since passing would
+    /// allow the king to be captured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{game::Game, Color};
+    ///
+    /// let mut g = Game::new();
+    /// g.make_null_move().unwrap();
+    /// assert_eq!(g.board().player, Color::Black);
+    /// g.undo_null_move().unwrap();
+    /// assert_eq!(g.board().player, Color::White);
+    /// ```
+    pub fn make_null_move(&mut self) -> Result<(), &'static str> {
+        let previous_state = self.history.last().unwrap();
+        if previous_state.in_check() {
+            return Err("cannot make a null move while in check");
+        }
+        let mut new_board = *previous_state;
+        new_board.make_null_move();
+
+        let num_reps = self.repetitions.entry(new_board.hash).or_insert(0);
+        *num_reps += 1;
+        self.history.push(new_board);
+        self.moves.push(Move::BAD_MOVE);
+
+        Ok(())
+    }
+
+    /// Take back a null move made by `make_null_move()`, restoring the board, hash, and en passant
+    /// square exactly as they were.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an `Err` if the most recent move was not a null move, in which
+    /// case the game is left unchanged.
+    pub fn undo_null_move(&mut self) -> Result<(), &'static str> {
+        if self.moves.last() != Some(&Move::BAD_MOVE) {
+            return Err("most recent move was not a null move");
+        }
+        self.undo()?;
+
+        Ok(())
+    }
+
     #[inline(always)]
     #[must_use]
     #[allow(clippy::missing_panics_doc)]
@@ -182,6 +259,69 @@ impl Game {
         *num_reps >= 3
     }
 
+    #[inline(always)]
+    #[must_use]
+    /// Get the number of plies since the last capture or pawn move, for the fifty-move rule.
+    /// This starts from the halfmove field of the FEN the game was created from, and is restored
+    /// along with the rest of the position on `undo()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::game::Game;
+    ///
+    /// let g = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 37 60").unwrap();
+    /// assert_eq!(g.halfmove_clock(), 37);
+    /// ```
+    pub fn halfmove_clock(&self) -> u16 {
+        u16::from(self.board().rule50())
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Determine whether the game is drawn by the fifty-move rule, meaning that 100 plies have
+    /// passed with no capture or pawn move.
+    pub fn is_fifty_move_draw(&self) -> bool {
+        self.halfmove_clock() >= 100
+    }
+
+    #[must_use]
+    /// Determine whether the current position has appeared at least `count` times in this game,
+    /// including the current occurrence.
+    /// Only positions since the last capture or pawn move are considered, since no position before
+    /// an irreversible move can ever be reached again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{game::Game, Move, Square};
+    ///
+    /// let mut g = Game::new();
+    /// for _ in 0..2 {
+    ///     g.make_move(Move::normal(Square::G1, Square::F3));
+    ///     g.make_move(Move::normal(Square::G8, Square::F6));
+    ///     g.make_move(Move::normal(Square::F3, Square::G1));
+    ///     g.make_move(Move::normal(Square::F6, Square::G8));
+    /// }
+    /// assert!(g.is_repetition(3));
+    /// assert!(!g.is_repetition(4));
+    /// ```
+    pub fn is_repetition(&self, count: usize) -> bool {
+        let b = self.board();
+        let window = (usize::from(b.rule50()) + 1).min(self.history.len());
+        // the side to move must match, so only every other position can be a repetition
+        let occurrences = self
+            .history
+            .iter()
+            .rev()
+            .take(window)
+            .step_by(2)
+            .filter(|old| old.hash == b.hash)
+            .count();
+
+        occurrences >= count
+    }
+
     #[must_use]
     /// Get the legal moves in this position.
     ///
@@ -194,12 +334,143 @@ impl Game {
         get_moves::<M>(self.board())
     }
 
+    #[must_use]
+    /// Perform a performance test on the move generator from the current position, collecting
+    /// statistics on the kinds of moves made at the leaves.
+    /// Repetition is not considered, so the results will match those of a plain perft.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::game::Game;
+    ///
+    /// let stats = Game::new().perft_with_captures_stats(2);
+    /// assert_eq!(stats.nodes, 400);
+    /// assert_eq!(stats.captures, 0);
+    /// ```
+    pub fn perft_with_captures_stats(&self, depth: u8) -> PerftStats {
+        /// Recursively search `b`, adding the leaf statistics to `stats`.
+        fn helper(b: &Board, depth: u8, stats: &mut PerftStats) {
+            for m in get_moves::<{ GenMode::All }>(b) {
+                let mut bcopy = *b;
+                bcopy.make_move(m);
+                if depth > 1 {
+                    helper(&bcopy, depth - 1, stats);
+                    continue;
+                }
+
+                stats.nodes += 1;
+                if b.is_move_capture(m) {
+                    stats.captures += 1;
+                }
+                if m.is_en_passant() {
+                    stats.en_passants += 1;
+                }
+                if m.is_castle() {
+                    stats.castles += 1;
+                }
+                if m.is_promotion() {
+                    stats.promotions += 1;
+                }
+                if !bcopy.checkers.is_empty() {
+                    stats.checks += 1;
+                }
+            }
+        }
+
+        let mut stats = PerftStats::default();
+        if depth == 0 {
+            stats.nodes = 1;
+        } else {
+            helper(self.board(), depth, &mut stats);
+        }
+
+        stats
+    }
+
     #[allow(clippy::len_without_is_empty)]
     #[must_use]
     /// Get the number of total positions in this history of this game.
     pub fn len(&self) -> usize {
         self.history.len()
     }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Export this game in Portable Game Notation.
+    /// Each pair in `headers` is written as a tag, in order, followed by the moves of the game in
+    /// standard algebraic notation.
+    /// The game ends with the value of the `Result` tag, or `*` if none is given.
+    ///
+    /// Since a [`Board`] does not track the fullmove number, move numbers always start from 1.
+    /// If the game did not start from the conventional starting position, the caller should supply
+    /// the `SetUp` and `FEN` tags.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an `Err` if the game contains a null move, which cannot be
+    /// written in PGN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{game::Game, Move, Square};
+    ///
+    /// let mut g = Game::new();
+    /// g.make_move(Move::normal(Square::E2, Square::E4));
+    /// g.make_move(Move::normal(Square::E7, Square::E5));
+    /// let headers = [("Result".to_string(), "*".to_string())];
+    /// assert_eq!(g.to_pgn(&headers), Ok("[Result \"*\"]\n\n1. e4 e5 *\n".to_string()));
+    /// ```
+    pub fn to_pgn(&self, headers: &[(String, String)]) -> Result<String, &'static str> {
+        let mut pgn = String::new();
+        for (name, value) in headers {
+            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(pgn, "[{name} \"{escaped}\"]").unwrap();
+        }
+        if !headers.is_empty() {
+            pgn.push('\n');
+        }
+
+        let mut tokens = Vec::with_capacity(self.moves.len() * 3 / 2 + 2);
+        let mut move_number = 1;
+        for (i, (&m, board)) in self.moves.iter().zip(&self.history).enumerate() {
+            match board.player {
+                Color::White => tokens.push(format!("{move_number}.")),
+                Color::Black if i == 0 => tokens.push(format!("{move_number}...")),
+                Color::Black => (),
+            }
+            if m == Move::BAD_MOVE {
+                return Err("cannot export a game containing a null move");
+            }
+            // every other move in the history was legal when it was played
+            tokens.push(m.to_algebraic(board).unwrap());
+            if board.player == Color::Black {
+                move_number += 1;
+            }
+        }
+        let result = headers
+            .iter()
+            .find(|(name, _)| name == "Result")
+            .map_or("*", |(_, value)| value.as_str());
+        tokens.push(result.to_string());
+
+        // keep lines of movetext under 80 characters, as the PGN standard suggests
+        let mut line_len = 0;
+        for token in tokens {
+            if line_len > 0 && line_len + 1 + token.len() > 79 {
+                pgn.push('\n');
+                line_len = 0;
+            } else if line_len > 0 {
+                pgn.push(' ');
+                line_len += 1;
+            }
+            line_len += token.len();
+            pgn += &token;
+        }
+        pgn.push('\n');
+
+        Ok(pgn)
+    }
 }
 
 impl Default for Game {
@@ -280,6 +551,159 @@ mod tests {
         assert_eq!(g.board(), &Board::from_fen(fen).unwrap());
     }
 
+    /// Play each move in `moves` on `g`, given in standard algebraic notation.
+    fn play_algebraic(g: &mut Game, moves: &[&str]) {
+        for s in moves {
+            g.make_move(Move::from_algebraic(s, g.board()).unwrap());
+        }
+    }
+
+    #[test]
+    /// Test that a short game with disambiguated knight moves and captures is exported correctly.
+    fn pgn_export() {
+        let mut g = Game::new();
+        play_algebraic(
+            &mut g,
+            &[
+                "d4", "d5", "Nf3", "Nf6", "Nbd2", "Nbd7", "e4", "dxe4", "Nxe4", "Nxe4",
+            ],
+        );
+        let headers = [
+            ("Event".to_string(), "Test".to_string()),
+            ("Site".to_string(), "?".to_string()),
+            ("Result".to_string(), "*".to_string()),
+        ];
+        assert_eq!(
+            g.to_pgn(&headers).unwrap(),
+            "[Event \"Test\"]\n[Site \"?\"]\n[Result \"*\"]\n\n\
+             1. d4 d5 2. Nf3 Nf6 3. Nbd2 Nbd7 4. e4 dxe4 5. Nxe4 Nxe4 *\n"
+        );
+    }
+
+    #[test]
+    /// Test that a game starting with Black to move and ending in mate is exported correctly.
+    fn pgn_black_first() {
+        let mut g = Game::from_fen("3k4/R7/1R6/5K2/8/8/8/8 b - - 0 1").unwrap();
+        play_algebraic(&mut g, &["Ke8", "Rb8#"]);
+        assert_eq!(
+            g.to_pgn(&[("Result".to_string(), "1-0".to_string())])
+                .unwrap(),
+            "[Result \"1-0\"]\n\n1... Ke8 2. Rb8# 1-0\n"
+        );
+        assert_eq!(Game::new().to_pgn(&[]).unwrap(), "*\n");
+    }
+
+    #[test]
+    /// Test that long games have their movetext wrapped to short lines.
+    fn pgn_wrapped() {
+        let mut g = Game::new();
+        for _ in 0..4 {
+            play_algebraic(&mut g, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+        }
+        let pgn = g.to_pgn(&[]).unwrap();
+        assert!(pgn.lines().count() > 1);
+        assert!(pgn.lines().all(|l| l.len() < 80));
+    }
+
+    #[test]
+    /// Test that a game with a null move on its stack is refused for export instead of panicking,
+    /// and exports normally once the null move is taken back.
+    fn pgn_null_move() {
+        let mut g = Game::new();
+        play_algebraic(&mut g, &["e4"]);
+        g.make_null_move().unwrap();
+        assert!(g.to_pgn(&[]).is_err());
+        g.undo_null_move().unwrap();
+        assert_eq!(g.to_pgn(&[]).unwrap(), "1. e4 *\n");
+    }
+
+    /// Play each move in `moves` on `g`, given as pairs of from-squares and to-squares.
+    fn play_all(g: &mut Game, moves: &[(Square, Square)]) {
+        for &(from_sq, to_sq) in moves {
+            g.make_move(Move::normal(from_sq, to_sq));
+        }
+    }
+
+    #[test]
+    /// Test that shuffling knights back and forth reaches a threefold repetition.
+    fn knight_shuffle_threefold() {
+        let shuffle = [
+            (Square::G1, Square::F3),
+            (Square::G8, Square::F6),
+            (Square::F3, Square::G1),
+            (Square::F6, Square::G8),
+        ];
+        let mut g = Game::new();
+        assert!(g.is_repetition(1));
+        assert!(!g.is_repetition(2));
+        play_all(&mut g, &shuffle);
+        assert!(g.is_repetition(2));
+        assert!(!g.is_repetition(3));
+        play_all(&mut g, &shuffle);
+        assert!(g.is_repetition(3));
+        assert!(g.drawn_by_repetition());
+    }
+
+    #[test]
+    /// Test that 100 reversible plies trigger the fifty-move rule, and that undoing one of them
+    /// takes the draw away.
+    fn fifty_move_draw() {
+        let mut g = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        let shuffle = [
+            (Square::A1, Square::A2),
+            (Square::E8, Square::D8),
+            (Square::A2, Square::A1),
+            (Square::D8, Square::E8),
+        ];
+        for _ in 0..25 {
+            assert!(!g.is_fifty_move_draw());
+            play_all(&mut g, &shuffle);
+        }
+        assert_eq!(g.halfmove_clock(), 100);
+        assert!(g.is_fifty_move_draw());
+
+        g.undo().unwrap();
+        assert_eq!(g.halfmove_clock(), 99);
+        assert!(!g.is_fifty_move_draw());
+    }
+
+    #[test]
+    /// Test that the halfmove clock is read from the FEN and reset by a pawn move.
+    fn halfmove_clock_from_fen() {
+        let mut g = Game::from_fen("4k3/4p3/8/8/8/8/4P3/4K3 w - - 42 80").unwrap();
+        assert_eq!(g.halfmove_clock(), 42);
+        play_all(&mut g, &[(Square::E1, Square::D1)]);
+        assert_eq!(g.halfmove_clock(), 43);
+        play_all(&mut g, &[(Square::E7, Square::E5)]);
+        assert_eq!(g.halfmove_clock(), 0);
+    }
+
+    #[test]
+    /// Test that positions from before a capture do not count toward a repetition.
+    fn capture_breaks_repetition() {
+        let mut g = Game::new();
+        play_all(
+            &mut g,
+            &[
+                (Square::G1, Square::F3),
+                (Square::G8, Square::F6),
+                (Square::F3, Square::G1),
+                (Square::F6, Square::G8),
+                (Square::B1, Square::C3),
+                (Square::D7, Square::D5),
+                (Square::C3, Square::D5),
+                (Square::D8, Square::D5),
+                (Square::G1, Square::F3),
+                (Square::D5, Square::D8),
+                (Square::F3, Square::G1),
+                (Square::D8, Square::D5),
+            ],
+        );
+        assert_eq!(g.board().rule50(), 4);
+        assert!(g.is_repetition(2));
+        assert!(!g.is_repetition(3));
+    }
+
     #[test]
     /// Test that undoing with no history results in an error.
     fn undo_fail() {
@@ -335,6 +759,155 @@ mod tests {
         assert_eq!(g, Game::new());
     }
 
+    /// Helper function to check that two boards are identical in every field, including the ones
+    /// which `Board`'s equality comparison ignores.
+    fn assert_identical(b1: &Board, b2: &Board) {
+        assert_eq!(b1, b2);
+        assert_eq!(b1.hash, b2.hash);
+        assert_eq!(b1.rule50(), b2.rule50());
+        assert_eq!(b1.checkers, b2.checkers);
+        assert_eq!(b1.king_sqs, b2.king_sqs);
+        assert_eq!(b1.pinned, b2.pinned);
+    }
+
+    #[test]
+    /// Test that every move in positions with captures, en passant, castling, and promotion can be
+    /// undone perfectly.
+    fn undo_special_moves() {
+        let fens = [
+            // kiwipete: castling both ways and plenty of captures
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            // exf6 is en passant
+            "rnbqkb1r/ppppp1pp/7n/4Pp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            // capture promotion is possible
+            "r4bkr/pPpq2pp/2n1b3/3n4/2BPp3/2P5/1P3PPP/RNBQK2R w KQ - 1 13",
+        ];
+        for fen in fens {
+            let mut g = Game::from_fen(fen).unwrap();
+            let orig_game = g.clone();
+            let orig_board = *g.board();
+            for m in g.get_moves::<{ GenMode::All }>() {
+                g.make_move(m);
+                assert_eq!(g.undo(), Ok(m));
+                assert_identical(g.board(), &orig_board);
+                assert_eq!(g, orig_game);
+            }
+        }
+    }
+
+    #[test]
+    /// Test that a long random game with interleaved undos always restores the exact state of the
+    /// game before each undone move.
+    fn undo_random_stress() {
+        let rng = fastrand::Rng::with_seed(12345);
+        let mut g = Game::new();
+        // the state of the game before each move currently on the stack
+        let mut snapshots: Vec<Game> = Vec::new();
+        let mut num_moves = 0;
+        while num_moves < 200 {
+            let moves = g.get_moves::<{ GenMode::All }>();
+            if moves.is_empty() || (!snapshots.is_empty() && rng.u8(..4) == 0) {
+                // game over, or chose to undo
+                let Some(snapshot) = snapshots.pop() else {
+                    break;
+                };
+                g.undo().unwrap();
+                assert_identical(g.board(), snapshot.board());
+                assert_eq!(g, snapshot);
+            } else {
+                snapshots.push(g.clone());
+                g.make_move(moves[rng.usize(..moves.len())]);
+                num_moves += 1;
+            }
+        }
+
+        // unwind everything
+        while let Some(snapshot) = snapshots.pop() {
+            g.undo().unwrap();
+            assert_identical(g.board(), snapshot.board());
+            assert_eq!(g, snapshot);
+        }
+        assert_eq!(g, Game::new());
+    }
+
+    /// Helper function to check the detailed perft statistics of a position.
+    /// `expected` contains the expected statistics at each depth, starting at depth 1.
+    fn perft_stats_helper(fen: &str, expected: &[PerftStats]) {
+        let g = Game::from_fen(fen).unwrap();
+        for (i, stats) in expected.iter().enumerate() {
+            let depth = u8::try_from(i + 1).unwrap();
+            assert_eq!(g.perft_with_captures_stats(depth), *stats);
+        }
+    }
+
+    #[test]
+    /// Test the detailed perft statistics of the start position.
+    fn perft_stats_start_position() {
+        perft_stats_helper(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[
+                PerftStats {
+                    nodes: 20,
+                    ..Default::default()
+                },
+                PerftStats {
+                    nodes: 400,
+                    ..Default::default()
+                },
+                PerftStats {
+                    nodes: 8_902,
+                    captures: 34,
+                    checks: 12,
+                    ..Default::default()
+                },
+            ],
+        );
+    }
+
+    #[test]
+    /// Test the detailed perft statistics of the Kiwipete position.
+    fn perft_stats_kiwipete() {
+        perft_stats_helper(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            &[
+                PerftStats {
+                    nodes: 48,
+                    captures: 8,
+                    castles: 2,
+                    ..Default::default()
+                },
+                PerftStats {
+                    nodes: 2_039,
+                    captures: 351,
+                    en_passants: 1,
+                    castles: 91,
+                    checks: 3,
+                    ..Default::default()
+                },
+                PerftStats {
+                    nodes: 97_862,
+                    captures: 17_102,
+                    en_passants: 45,
+                    castles: 3_162,
+                    checks: 993,
+                    ..Default::default()
+                },
+            ],
+        );
+    }
+
+    #[test]
+    /// Test that a depth-zero perft contains only the root.
+    fn perft_stats_depth_zero() {
+        assert_eq!(
+            Game::new().perft_with_captures_stats(0),
+            PerftStats {
+                nodes: 1,
+                ..Default::default()
+            }
+        );
+    }
+
     #[test]
     /// Test that a king can escape check without capturing the checker.
     fn king_escape_without_capture() {
@@ -354,4 +927,42 @@ mod tests {
             assert!(moves.contains(em));
         }
     }
+
+    #[test]
+    /// Test that a null move followed by an undo restores the exact board, including the hash and
+    /// en passant square.
+    fn null_move_round_trip() {
+        let mut g = Game::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3")
+            .unwrap();
+        let before = *g.board();
+        g.make_null_move().unwrap();
+        let passed = *g.board();
+        assert_eq!(passed.player, Color::Black);
+        assert_eq!(passed.en_passant_square, None);
+        assert_eq!(
+            passed,
+            Board::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR b KQkq - 1 3")
+                .unwrap()
+        );
+        assert_ne!(passed.hash, before.hash);
+
+        assert_eq!(g.undo_null_move(), Ok(()));
+        assert_eq!(*g.board(), before);
+        assert_eq!(g.board().en_passant_square, Some(Square::F6));
+        assert_eq!(g.len(), 1);
+    }
+
+    #[test]
+    /// Test that null moves are rejected in check, and that `undo_null_move` only undoes null
+    /// moves.
+    fn null_move_rejected() {
+        let mut g = Game::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+        assert!(g.make_null_move().is_err());
+        assert_eq!(g.len(), 1);
+
+        let mut g = Game::new();
+        g.make_move(Move::normal(Square::E2, Square::E4));
+        assert!(g.undo_null_move().is_err());
+        assert_eq!(g.len(), 2);
+    }
 }
@@ -18,7 +18,7 @@
 
 //! Magic bitboards, used for generating bishop, knight, and rook moves.
 
-use super::{Bitboard, Direction, Square};
+use super::{bitboard::directional_attacks, Bitboard, Direction, Square};
 
 use once_cell::sync::Lazy;
 
@@ -32,6 +32,14 @@ use std::{
 /// No other magic tables should be generated, as they will be identical to this one.
 pub static MAGIC: Lazy<AttacksTable> = Lazy::new(AttacksTable::load);
 
+/// Whether attack tables are indexed with the PEXT instruction instead of magic multiplication.
+/// This requires both the `pext` feature and a target with BMI2 enabled.
+const USE_PEXT: bool = cfg!(all(
+    feature = "pext",
+    target_arch = "x86_64",
+    target_feature = "bmi2"
+));
+
 /// The number of times to try generating magics.
 const NUM_MAGIC_TRIES: u64 = 10_000_000;
 
@@ -259,6 +267,8 @@ impl AttacksTable {
 
     #[inline(always)]
     /// Get the attacks that a rook on `sq` could make with the reference table `table`.
+    /// Depending on the `pext` feature, the table will be indexed either by magic multiplication
+    /// or by the PEXT instruction, but the result is the same either way.
     ///
     /// # Examples
     ///
@@ -329,10 +339,13 @@ fn load_magic_helper(table: &mut [SquareAttacks; 64], is_rook: bool) {
             table[i].magic = SAVED_BISHOP_MAGICS[i];
             table[i].shift = 64 - BISHOP_BITS[i];
         }
-        table[i]
-            .attacks
-            .resize(1 << (64 - table[i].shift), Bitboard::EMPTY);
         let num_points = table[i].mask.len();
+        let table_len = if USE_PEXT {
+            1 << num_points
+        } else {
+            1 << (64 - table[i].shift)
+        };
+        table[i].attacks.resize(table_len, Bitboard::EMPTY);
         for j in 0..(1 << num_points) {
             let occupancy = index_to_occupancy(j, table[i].mask);
             let directions = if is_rook {
@@ -341,7 +354,7 @@ fn load_magic_helper(table: &mut [SquareAttacks; 64], is_rook: bool) {
                 &Direction::BISHOP_DIRECTIONS
             };
             let attack = directional_attacks(sq, directions, occupancy);
-            let key = compute_magic_key(occupancy, table[i].magic, table[i].shift);
+            let key = table_key(occupancy, &table[i]);
             if table[i].attacks[key].is_empty() {
                 table[i].attacks[key] = attack;
             } else if table[i].attacks[key] != attack {
@@ -363,12 +376,35 @@ fn get_attacks(occupancy: Bitboard, sq: Square, table: &[SquareAttacks; 64]) ->
     // Additionally, we can trust that the key was masked correctly in `compute_magic_key` as it was
     // shifted out properly.
     let magic_data = unsafe { table.get_unchecked(sq as usize) };
-    let masked_occupancy = occupancy & magic_data.mask;
-    let key = compute_magic_key(masked_occupancy, magic_data.magic, magic_data.shift);
+    let key = table_key(occupancy, magic_data);
 
     unsafe { *magic_data.attacks.get_unchecked(key) }
 }
 
+#[inline(always)]
+#[cfg(not(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2")))]
+/// Get the index into the attacks of `magic_data` for an occupancy, using magic multiplication.
+fn table_key(occupancy: Bitboard, magic_data: &SquareAttacks) -> usize {
+    compute_magic_key(
+        occupancy & magic_data.mask,
+        magic_data.magic,
+        magic_data.shift,
+    )
+}
+
+#[inline(always)]
+#[cfg(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2"))]
+#[allow(clippy::cast_possible_truncation)]
+/// Get the index into the attacks of `magic_data` for an occupancy, using the PEXT instruction.
+/// PEXT packs the bits of the occupancy under the mask into a dense index, which is exactly the
+/// index used by `index_to_occupancy`.
+fn table_key(occupancy: Bitboard, magic_data: &SquareAttacks) -> usize {
+    // SAFETY: this function is only compiled when BMI2 is enabled for the target.
+    unsafe {
+        std::arch::x86_64::_pext_u64(u64::from(occupancy), u64::from(magic_data.mask)) as usize
+    }
+}
+
 #[inline(always)]
 /// Use magic hashing to get the index to look up attacks in a bitboad.
 fn compute_magic_key(occupancy: Bitboard, magic: Bitboard, shift: u8) -> usize {
@@ -538,59 +574,6 @@ fn index_to_occupancy(index: usize, mask: Bitboard) -> Bitboard {
     result
 }
 
-/// Construct the squares attacked by the pieces at `sq` if it could move along the directions in
-/// `dirs` when the board is occupied by the pieces in `occupancy`.
-///
-/// This is slow and should only be used for generatic magic bitboards (instead of for move
-/// generation.
-pub(crate) const fn directional_attacks(
-    sq: Square,
-    dirs: &[Direction],
-    occupancy: Bitboard,
-) -> Bitboard {
-    // behold: much hackery for making this work as a const fn
-    let mut result = Bitboard::EMPTY;
-    let mut dir_idx = 0;
-    while dir_idx < dirs.len() {
-        let dir = dirs[dir_idx];
-        let mut current_square = sq;
-        let mut loop_idx = 0;
-        while loop_idx < 7 {
-            let next_square_int: i16 = current_square as i16
-                + unsafe {
-                    // SAFETY: All values for an `i8` are valid.
-                    transmute::<Direction, i8>(dir) as i16
-                };
-            if next_square_int < 0 || 64 <= next_square_int {
-                break;
-            }
-            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            let next_square: Square = unsafe {
-                // SAFETY: We checked that this next square was in the range 0..63, which is how a
-                // square is represented.
-                transmute(next_square_int as u8)
-            };
-            if next_square.chebyshev_to(current_square) > 1 {
-                break;
-            }
-            result = Bitboard::new(
-                unsafe {
-                    // SAFETY: Any value is OK for an int.
-                    transmute::<Bitboard, u64>(result)
-                } | 1 << next_square as u8,
-            );
-            if occupancy.contains(next_square) {
-                break;
-            }
-            current_square = next_square;
-            loop_idx += 1;
-        }
-        dir_idx += 1;
-    }
-
-    result
-}
-
 #[inline(always)]
 /// Generate a random, mostly-empty bitboard.
 fn random_sparse_bitboard() -> Bitboard {
@@ -745,4 +728,26 @@ mod tests {
             assert_eq!(attacks[i], resulting_attack);
         }
     }
+
+    #[test]
+    /// Test that table lookups agree exactly with slow ray-walking attacks for a sampling of
+    /// occupancies on every square.
+    /// This holds whichever backend (magic multiplication or PEXT) the table was built with.
+    fn table_matches_rays() {
+        let table = AttacksTable::load();
+        let rng = fastrand::Rng::with_seed(577);
+        for sq in Bitboard::ALL {
+            for _ in 0..64 {
+                let occupancy = Bitboard::new(rng.u64(..) & rng.u64(..));
+                assert_eq!(
+                    table.rook_attacks(occupancy, sq),
+                    directional_attacks(sq, &Direction::ROOK_DIRECTIONS, occupancy)
+                );
+                assert_eq!(
+                    table.bishop_attacks(occupancy, sq),
+                    directional_attacks(sq, &Direction::BISHOP_DIRECTIONS, occupancy)
+                );
+            }
+        }
+    }
 }
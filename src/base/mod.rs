@@ -19,6 +19,32 @@
 #![allow(clippy::inline_always, clippy::too_many_lines)]
 
 //! Shared data types and useful basic definitions found across the entire Tomato engine.
+//!
+//! The `Bitboard`, `Square`, and `Direction` types only use `core`, so that they can be reused in
+//! `no_std` contexts.
+//! The example below checks this by building their modules into a `no_std` crate, where `std` is
+//! linked but cannot be named.
+//!
+//! ```
+//! #![no_std]
+//! extern crate std as _;
+//!
+//! #[path = "bitboard.rs"]
+//! mod bitboard;
+//! #[path = "direction.rs"]
+//! mod direction;
+//! #[path = "square.rs"]
+//! mod square;
+//!
+//! use bitboard::Bitboard;
+//! use direction::Direction;
+//! use square::Square;
+//!
+//! fn main() {
+//!     let bb = Bitboard::from(Square::E4) | Bitboard::from(Square::E4 + Direction::NORTH);
+//!     assert_eq!(bb.len(), 2);
+//! }
+//! ```
 
 // Many module elements are re-exported to make names more ergonomic to access.
 
@@ -26,7 +52,7 @@ mod bitboard;
 pub use bitboard::Bitboard;
 
 mod board;
-pub use board::Board;
+pub use board::{Board, BoardBuilder};
 
 mod castling;
 use castling::CastleRights;
@@ -37,6 +63,8 @@ pub use color::Color;
 mod direction;
 pub use direction::Direction;
 
+pub mod epd;
+
 pub mod game;
 
 mod magic;
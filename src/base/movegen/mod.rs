@@ -21,9 +21,9 @@
 #[cfg(test)]
 mod tests;
 
-use std::{convert::TryFrom, mem::transmute, time::Instant};
+use std::{collections::HashSet, convert::TryFrom, mem::transmute, ops::Deref, time::Instant};
 
-use super::{bitboard::Bitboard, Board, Color, Direction, Move, Piece, Square, MAGIC};
+use super::{bitboard::Bitboard, game::Game, Board, Color, Direction, Move, Piece, Square, MAGIC};
 
 /// A lookup table for the legal squares a knight to move to from a given square.
 ///
@@ -84,6 +84,36 @@ pub const PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
     create_step_attacks(&[Direction::SOUTHEAST, Direction::SOUTHWEST], 1),
 ];
 
+#[must_use]
+/// Get the squares attacked by a piece of type `pt` and color `color` standing on `sq`, when the
+/// occupied squares of the board are `occupancy`.
+/// The color only matters for pawns, and the occupancy only matters for sliders.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{movegen::{piece_attacks, PAWN_ATTACKS}, Bitboard, Color, Piece, Square};
+///
+/// assert_eq!(
+///     piece_attacks(Piece::Pawn, Color::Black, Square::E5, Bitboard::EMPTY),
+///     PAWN_ATTACKS[Color::Black as usize][Square::E5 as usize]
+/// );
+/// // a blocker on e4 stops the rook from seeing e3
+/// let rook = piece_attacks(Piece::Rook, Color::White, Square::E1, Bitboard::from(Square::E4));
+/// assert!(rook.contains(Square::E4));
+/// assert!(!rook.contains(Square::E5));
+/// ```
+pub fn piece_attacks(pt: Piece, color: Color, sq: Square, occupancy: Bitboard) -> Bitboard {
+    match pt {
+        Piece::Pawn => PAWN_ATTACKS[color as usize][sq as usize],
+        Piece::Knight => KNIGHT_MOVES[sq as usize],
+        Piece::Bishop => MAGIC.bishop_attacks(occupancy, sq),
+        Piece::Rook => MAGIC.rook_attacks(occupancy, sq),
+        Piece::Queen => MAGIC.rook_attacks(occupancy, sq) | MAGIC.bishop_attacks(occupancy, sq),
+        Piece::King => KING_MOVES[sq as usize],
+    }
+}
+
 /// Get the step attacks that could be made by moving in `dirs` from each point in the square.
 ///
 /// Exclude the steps that travel more than `max_dist` (this prevents overflow around the edges of
@@ -120,7 +150,7 @@ const fn create_step_attacks(dirs: &[Direction], max_dist: u8) -> [Bitboard; 64]
     attacks
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, std::marker::ConstParamTy)]
 /// The possible modes for move generation.
 /// They are inteded for use as const-generic parameters for `get_moves()`.
 pub enum GenMode {
@@ -128,8 +158,16 @@ pub enum GenMode {
     All,
     /// The mode identifier for `get_moves()` to generate captures only.
     Captures,
-    /// The mode identifier for `get_moves()` to generate non-captures only.
+    /// The mode identifier for `get_moves()` to generate tactical moves only: captures (including
+    /// en passant) and promotions, whether or not they capture.
+    Tactics,
+    /// The mode identifier for `get_moves()` to generate quiet moves only: moves which neither
+    /// capture nor promote.
+    /// Together, `Quiets` and `Tactics` generate every legal move exactly once.
     Quiets,
+    /// The mode identifier for `get_moves()` to generate only check evasions.
+    /// If the player to move is not in check, no moves will be generated.
+    Evasions,
 }
 
 #[must_use]
@@ -261,7 +299,9 @@ pub fn is_legal(m: Move, b: &Board) -> bool {
             Bitboard::between(b.king_sqs[player_idx], checker_sq) | Bitboard::from(checker_sq);
 
         if let Some(ep_sq) = b.en_passant_square {
-            if pt == Piece::Pawn && (checker_sq == ep_sq - player.pawn_direction()) {
+            let ep_pawn_sq = ep_sq - player.pawn_direction();
+            debug_assert!((b[Piece::Pawn] & b[!player]).contains(ep_pawn_sq));
+            if pt == Piece::Pawn && checker_sq == ep_pawn_sq {
                 // allow en passants that let us escape check
                 targets.insert(ep_sq);
             }
@@ -301,11 +341,13 @@ pub fn is_legal(m: Move, b: &Board) -> bool {
 ///
 /// `M` is the generation mode of move generation: it specifies which subset of all legal moves to
 /// generate.
-/// There are currently 3 legal generation modes:
+/// There are currently 5 legal generation modes:
 ///
 /// * `GenMode::All` will generate all legal moves.
 /// * `GenMode::Captures` will generate all captures, including en passant.
-/// * `GenMode::Quiets` will generate all quiet (i.e. non-capture) moves.
+/// * `GenMode::Tactics` will generate all captures and all promotions.
+/// * `GenMode::Quiets` will generate all quiet (i.e. non-capture, non-promotion) moves.
+/// * `GenMode::Evasions` will generate all check evasions, or nothing if not in check.
 ///
 /// `get_moves()` will make no regard to whether the position is drawn by
 /// repetition, 50-move-rule, or by insufficient material.
@@ -338,6 +380,20 @@ pub fn is_legal(m: Move, b: &Board) -> bool {
 /// # }
 /// ```
 ///
+/// Generate tactical moves:
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>>{
+/// use tomato::base::{Board, Move, movegen::{GenMode, get_moves}, Piece, Square};
+///
+/// // White can promote on b8, but there is nothing to capture.
+/// let b = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1")?;
+/// assert!(get_moves::<{GenMode::Captures}>(&b).is_empty());
+/// assert!(get_moves::<{GenMode::Tactics}>(&b)
+///     .contains(&Move::promoting(Square::B7, Square::B8, Piece::Queen)));
+/// # Ok(())
+/// # }
+/// ```
+///
 /// Generate quiet moves:
 ///
 /// ```
@@ -349,6 +405,23 @@ pub fn is_legal(m: Move, b: &Board) -> bool {
 ///     assert!(!b.is_move_capture(m));
 /// }
 /// ```
+///
+/// Generate check evasions:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>>{
+/// use tomato::base::{Board, movegen::{GenMode, get_moves}};
+///
+/// // Nobody is in check at the start of the game.
+/// assert!(get_moves::<{GenMode::Evasions}>(&Board::new()).is_empty());
+///
+/// // Scholar's mate, but White played Qxf7+ without the bishop's support. The only evasion is
+/// // Kxf7.
+/// let b = Board::from_fen("r1bqkbnr/pppp1Qpp/2n5/4p3/4P3/8/PPPP1PPP/RNB1KBNR b KQkq - 0 3")?;
+/// assert_eq!(get_moves::<{GenMode::Evasions}>(&b).len(), 1);
+/// # Ok(())
+/// # }
+/// ```
 pub fn get_moves<const M: GenMode>(b: &Board) -> Vec<Move> {
     let mut moves;
     let in_check = !b.checkers.is_empty();
@@ -358,13 +431,17 @@ pub fn get_moves<const M: GenMode>(b: &Board) -> Vec<Move> {
         // legal evasions if the king is in check
         moves = Vec::with_capacity(8);
         evasions::<M>(b, &mut moves);
+    } else if M == GenMode::Evasions {
+        // not in check, so there is nothing to evade
+        moves = Vec::new();
     } else {
         // in the overwhelming majority of cases, there are fewer than 50
         // legal moves total
         let capacity = match M {
             GenMode::All => 50,
-            GenMode::Captures => 8,
+            GenMode::Captures | GenMode::Tactics => 8,
             GenMode::Quiets => 40,
+            GenMode::Evasions => unreachable!(),
         };
         moves = Vec::with_capacity(capacity);
         non_evasions::<M>(b, &mut moves);
@@ -373,6 +450,82 @@ pub fn get_moves<const M: GenMode>(b: &Board) -> Vec<Move> {
     moves
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A list of moves generated from a single position.
+/// Each move in a `MoveList` is distinct; this invariant is checked in debug builds.
+pub struct MoveList(Vec<Move>);
+
+impl MoveList {
+    #[must_use]
+    /// Construct a new `MoveList` from a collection of moves.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, this function will panic if any move appears in `moves` more than once.
+    pub fn new(moves: Vec<Move>) -> MoveList {
+        debug_assert!(
+            !has_duplicates(&moves),
+            "move list {moves:?} contains duplicates"
+        );
+        MoveList(moves)
+    }
+
+    #[must_use]
+    /// Generate the moves of a given mode from a position, as in `get_moves`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Board, movegen::{GenMode, MoveList}};
+    ///
+    /// let moves = MoveList::generate::<{ GenMode::All }>(&Board::new());
+    /// assert_eq!(moves.len(), 20);
+    /// ```
+    pub fn generate<const M: GenMode>(b: &Board) -> MoveList {
+        MoveList::new(get_moves::<M>(b))
+    }
+
+    #[must_use]
+    /// Convert this list back into a plain vector of moves.
+    pub fn into_inner(self) -> Vec<Move> {
+        self.0
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.0
+    }
+}
+
+impl IntoIterator for MoveList {
+    type Item = Move;
+    type IntoIter = std::vec::IntoIter<Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[must_use]
+/// Determine whether any move appears more than once in `moves`.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Move, Square, movegen::has_duplicates};
+///
+/// let m = Move::normal(Square::E2, Square::E4);
+/// assert!(!has_duplicates(&[m]));
+/// assert!(has_duplicates(&[m, m]));
+/// ```
+pub fn has_duplicates(moves: &[Move]) -> bool {
+    let mut seen = HashSet::with_capacity(moves.len());
+    !moves.iter().all(|&m| seen.insert(m))
+}
+
 #[must_use]
 /// Determine whether the player to move have any legal moves in this position.
 ///
@@ -549,11 +702,17 @@ pub fn is_square_attacked_by(board: &Board, sq: Square, color: Color) -> bool {
 fn non_evasions<const M: GenMode>(b: &Board, moves: &mut Vec<Move>) {
     let target_sqs = match M {
         GenMode::All => !b[b.player],
-        GenMode::Captures => !b[b.player] & b[!b.player],
+        GenMode::Captures | GenMode::Tactics => !b[b.player] & b[!b.player],
         GenMode::Quiets => !b.occupancy(),
+        // evasions are only generated when in check
+        GenMode::Evasions => unreachable!(),
     };
 
     let mut pawn_targets = target_sqs;
+    if M == GenMode::Tactics {
+        // pawns may also push to promote
+        pawn_targets |= b.player.pawn_promote_rank() & !b.occupancy();
+    }
     if M != GenMode::Quiets {
         if let Some(ep_sq) = b.en_passant_square {
             pawn_targets.insert(ep_sq);
@@ -564,7 +723,7 @@ fn non_evasions<const M: GenMode>(b: &Board, moves: &mut Vec<Move>) {
     normal_piece_assistant(b, moves, target_sqs);
 
     // generate king moves
-    if M != GenMode::Captures {
+    if M != GenMode::Captures && M != GenMode::Tactics {
         castles(b, moves);
     }
     king_move_non_castle(b, moves, target_sqs);
@@ -583,18 +742,24 @@ fn evasions<const M: GenMode>(b: &Board, moves: &mut Vec<Move>) {
         // SAFETY: We checked that the square is nonzero.
         let checker_sq = unsafe { Square::unsafe_from(b.checkers) };
         // Look for blocks or captures
-        let mut target_sqs = !b[b.player] & Bitboard::between(king_sq, checker_sq) | b.checkers;
+        let block_sqs = !b[b.player] & Bitboard::between(king_sq, checker_sq) | b.checkers;
+        let mut target_sqs = block_sqs;
         match M {
-            GenMode::All => (),
-            GenMode::Captures => target_sqs &= b[!player],
+            GenMode::All | GenMode::Evasions => (),
+            GenMode::Captures | GenMode::Tactics => target_sqs &= b[!player],
             GenMode::Quiets => target_sqs &= !b[!player],
         }
 
         let mut pawn_targets = target_sqs;
+        if M == GenMode::Tactics {
+            // pawns may also block by pushing to promote
+            pawn_targets |= block_sqs & player.pawn_promote_rank();
+        }
         if M != GenMode::Quiets {
             if let Some(ep_sq) = b.en_passant_square {
                 // can en passant save us from check?
                 let ep_attacker_sq = ep_sq - player.pawn_direction();
+                debug_assert!((b[Piece::Pawn] & b[!player]).contains(ep_attacker_sq));
                 if b.checkers.contains(ep_attacker_sq) {
                     pawn_targets.insert(ep_sq);
                 }
@@ -606,8 +771,8 @@ fn evasions<const M: GenMode>(b: &Board, moves: &mut Vec<Move>) {
     }
 
     let king_targets = match M {
-        GenMode::All => !b[b.player],
-        GenMode::Captures => !b[b.player] & b[!player],
+        GenMode::All | GenMode::Evasions => !b[b.player],
+        GenMode::Captures | GenMode::Tactics => !b[b.player] & b[!player],
         GenMode::Quiets => !b.occupancy(),
     };
     king_move_non_castle(b, moves, king_targets);
@@ -636,39 +801,18 @@ pub fn square_attackers(board: &Board, sq: Square, color: Color) -> Bitboard {
     square_attackers_occupancy(board, sq, color, board.occupancy())
 }
 
+#[must_use]
 /// Same functionality as `square_attackers`, but uses the provided `occupancy` bitboard (as
 /// opposed to the board's occupancy.)
-fn square_attackers_occupancy(
+/// This is `Board::attackers_to` restricted to the pieces of `color`, so pieces which are missing
+/// from `occupancy` are never returned as attackers.
+pub fn square_attackers_occupancy(
     board: &Board,
     sq: Square,
     color: Color,
     occupancy: Bitboard,
 ) -> Bitboard {
-    let mut attackers = Bitboard::EMPTY;
-    let color_bb = board[color];
-    // Check for pawn attacks
-    let pawn_vision = PAWN_ATTACKS[!color as usize][sq as usize];
-    attackers |= pawn_vision & board[Piece::Pawn];
-
-    // Check for knight attacks
-    let knight_vision = KNIGHT_MOVES[sq as usize];
-    attackers |= knight_vision & board[Piece::Knight];
-
-    let queens_bb = board[Piece::Queen];
-
-    // Check for rook/horizontal queen attacks
-    let rook_vision = MAGIC.rook_attacks(occupancy, sq);
-    attackers |= rook_vision & (queens_bb | board[Piece::Rook]);
-
-    // Check for bishop/diagonal queen attacks
-    let bishop_vision = MAGIC.bishop_attacks(occupancy, sq);
-    attackers |= bishop_vision & (queens_bb | board[Piece::Bishop]);
-
-    // Check for king attacks
-    let king_vision = KING_MOVES[sq as usize];
-    attackers |= king_vision & board[Piece::King];
-
-    attackers & color_bb
+    board.attackers_to(sq, occupancy) & board[color]
 }
 
 /// Generate the moves all pawns can make and populate `moves` with those moves.
@@ -812,11 +956,13 @@ fn pawn_assistant<const M: GenMode>(b: &Board, moves: &mut Vec<Move>, target: Bi
         singles &= target;
 
         // promotion single-moves
-        for to_sq in singles & rank8 {
-            let from_sq = to_sq - direction;
-            for pt in Piece::PROMOTING {
-                let m = Move::promoting(from_sq, to_sq, pt);
-                moves.push(m);
+        if M != GenMode::Quiets {
+            for to_sq in singles & rank8 {
+                let from_sq = to_sq - direction;
+                for pt in Piece::PROMOTING {
+                    let m = Move::promoting(from_sq, to_sq, pt);
+                    moves.push(m);
+                }
             }
         }
 
@@ -1039,3 +1185,106 @@ pub fn perft(fen: &str, depth: u8) -> u64 {
 
     num_nodes
 }
+
+#[must_use]
+#[allow(clippy::missing_panics_doc)]
+/// Count the number of leaf positions reachable in exactly `depth` moves from the current position
+/// of `game`.
+/// Unlike `perft()`, this plays every move through `Game::make_move()` and `Game::undo()`, so it
+/// exercises the same path as the search.
+/// `game` is returned to its original state afterward.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{game::Game, movegen::perft_game};
+///
+/// let mut g = Game::new();
+/// assert_eq!(perft_game(&mut g, 3), 8_902);
+/// assert_eq!(g, Game::new());
+/// ```
+pub fn perft_game(game: &mut Game, depth: u8) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = game.get_moves::<{ GenMode::All }>();
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    let mut total = 0;
+    for m in moves {
+        game.make_move(m);
+        total += perft_game(game, depth - 1);
+        game.undo().unwrap();
+    }
+
+    total
+}
+
+#[must_use]
+#[allow(clippy::missing_panics_doc)]
+/// Count the number of leaf positions reachable in exactly `depth` moves from the current position
+/// of `game`, split up by the first move played.
+/// The counts are given in the order in which the moves were generated.
+/// Comparing this against another engine's output is the quickest way to track down a move
+/// generation bug.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{game::Game, movegen::perft_divide, Move, Square};
+///
+/// let mut g = Game::new();
+/// let divide = perft_divide(&mut g, 2);
+/// assert_eq!(divide.len(), 20);
+/// assert!(divide.contains(&(Move::normal(Square::E2, Square::E4), 20)));
+/// ```
+pub fn perft_divide(game: &mut Game, depth: u8) -> Vec<(Move, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+
+    game.get_moves::<{ GenMode::All }>()
+        .into_iter()
+        .map(|m| {
+            game.make_move(m);
+            let count = perft_game(game, depth - 1);
+            game.undo().unwrap();
+            (m, count)
+        })
+        .collect()
+}
+
+#[cfg(feature = "parallel")]
+#[must_use]
+/// Count the number of leaf positions reachable in exactly `depth` moves from the current position
+/// of `game`, splitting the work across a thread pool.
+/// Each root move is searched by a separate task on its own copy of `game`, so the result is
+/// always the same as `perft_game()`.
+///
+/// This requires the `parallel` feature.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{game::Game, movegen::perft_parallel};
+///
+/// assert_eq!(perft_parallel(&Game::new(), 3), 8_902);
+/// ```
+pub fn perft_parallel(game: &Game, depth: u8) -> u64 {
+    use rayon::prelude::*;
+
+    if depth <= 1 {
+        return perft_game(&mut game.clone(), depth);
+    }
+
+    game.get_moves::<{ GenMode::All }>()
+        .into_par_iter()
+        .map(|m| {
+            let mut subgame = game.clone();
+            subgame.make_move(m);
+            perft_game(&mut subgame, depth - 1)
+        })
+        .sum()
+}
@@ -1,5 +1,100 @@
 use super::*;
 
+#[test]
+/// Test that move generation never emits the same move twice, and that no move is generated as
+/// both a capture and a quiet.
+fn moves_unique() {
+    let fens = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "rnbqkbnr/ppppp1pp/8/5p2/4P3/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 2",
+        "r1bqkbnr/pppp1Qpp/2n5/4p3/4P3/8/PPPP1PPP/RNB1KBNR b KQkq - 0 3",
+    ];
+    for fen in fens {
+        let b = Board::from_fen(fen).unwrap();
+        let all = get_moves::<{ GenMode::All }>(&b);
+        assert!(!has_duplicates(&all), "duplicate moves in {fen}: {all:?}");
+        assert_eq!(MoveList::generate::<{ GenMode::All }>(&b).len(), all.len());
+
+        let mut split = get_moves::<{ GenMode::Captures }>(&b);
+        split.extend(get_moves::<{ GenMode::Quiets }>(&b));
+        assert!(
+            !has_duplicates(&split),
+            "duplicate moves in {fen}: {split:?}"
+        );
+    }
+}
+
+#[test]
+/// Test that tactical move generation is a strict subset of all moves, containing exactly the
+/// captures and promotions.
+fn tactics_subset() {
+    let fens = [
+        // promotions by push and by capture, with pieces hanging
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/ppppp1pp/8/5p2/4P3/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 2",
+        // in check, where a promotion blocks the check
+        "K6r/1P6/8/8/8/8/8/4k3 w - - 0 1",
+        "r3k3/1P6/8/8/8/8/8/K3r3 w - - 0 1",
+    ];
+    for fen in fens {
+        let b = Board::from_fen(fen).unwrap();
+        let all = get_moves::<{ GenMode::All }>(&b);
+        let mut tactics = get_moves::<{ GenMode::Tactics }>(&b);
+        assert!(!has_duplicates(&tactics), "duplicate moves in {fen}");
+        assert!(tactics.len() < all.len(), "not a strict subset in {fen}");
+
+        let mut expected: Vec<Move> = all
+            .into_iter()
+            .filter(|&m| b.is_move_capture(m) || m.promote_type().is_some())
+            .collect();
+        expected.sort_by_key(|m| m.value());
+        tactics.sort_by_key(|m| m.value());
+        assert_eq!(tactics, expected, "wrong tactical moves in {fen}");
+    }
+}
+
+#[test]
+/// Test that quiet and tactical move generation are disjoint, and that together they generate
+/// every legal move.
+fn quiets_tactics_partition() {
+    let fens = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "rnbqkbnr/ppppp1pp/8/5p2/4P3/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 2",
+        "K6r/1P6/8/8/8/8/8/4k3 w - - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    ];
+    for fen in fens {
+        let b = Board::from_fen(fen).unwrap();
+        let quiets = get_moves::<{ GenMode::Quiets }>(&b);
+        for &m in &quiets {
+            assert!(!b.is_move_capture(m) && m.promote_type().is_none());
+        }
+
+        let mut union = quiets;
+        union.extend(get_moves::<{ GenMode::Tactics }>(&b));
+        assert!(
+            !has_duplicates(&union),
+            "quiets and tactics overlap in {fen}"
+        );
+
+        let mut all = get_moves::<{ GenMode::All }>(&b);
+        union.sort_by_key(|m| m.value());
+        all.sort_by_key(|m| m.value());
+        assert_eq!(
+            union, all,
+            "quiets and tactics do not cover all moves in {fen}"
+        );
+    }
+}
+
 #[test]
 /// Test that we can play Qf3+, the critical move in the Fried Liver opening.
 fn best_queen_fried_liver() {
@@ -203,6 +298,48 @@ fn startpos_has_moves() {
     assert!(has_moves(&Board::default()));
 }
 
+#[test]
+/// Test that no evasions are generated when the player to move is not in check.
+fn no_evasions_out_of_check() {
+    assert!(get_moves::<{ GenMode::Evasions }>(&Board::default()).is_empty());
+}
+
+#[test]
+/// Test that only king moves are generated to evade a double check, even when other pieces could
+/// capture or block one of the checkers.
+fn double_check_evasions() {
+    let b = Board::from_fen("4r2k/8/8/R7/8/3n4/8/3RK3 w - - 0 1").unwrap();
+    let moves = get_moves::<{ GenMode::Evasions }>(&b);
+    assert_eq!(moves.len(), 2);
+    assert!(moves.contains(&Move::normal(Square::E1, Square::D2)));
+    assert!(moves.contains(&Move::normal(Square::E1, Square::F1)));
+}
+
+#[test]
+/// Test that the evasions from a single slider check are exactly the legal moves in the position.
+fn single_slider_evasions() {
+    // the rook can be captured by the bishop, blocked by the knight or rook, or the king can move
+    let b = Board::from_fen("4r2k/8/8/R7/B7/8/3N4/4K3 w - - 0 1").unwrap();
+    let moves = get_moves::<{ GenMode::Evasions }>(&b);
+    let mut legal_moves = Vec::new();
+    for from_sq in b[b.player] {
+        for to_sq in Bitboard::ALL {
+            let m = Move::normal(from_sq, to_sq);
+            if is_legal(m, &b) {
+                legal_moves.push(m);
+            }
+        }
+    }
+
+    assert_eq!(moves.len(), legal_moves.len());
+    for m in &legal_moves {
+        assert!(moves.contains(m));
+    }
+    assert!(moves.contains(&Move::normal(Square::A4, Square::E8)));
+    assert!(moves.contains(&Move::normal(Square::D2, Square::E4)));
+    assert!(moves.contains(&Move::normal(Square::A5, Square::E5)));
+}
+
 /// Tests that mates are correct.
 mod mates {
     use super::*;
@@ -259,6 +396,33 @@ mod perft {
         }
     }
 
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    /// Test the `Game`-based perft against the known values for the starting position.
+    fn game_start_position() {
+        let mut g = Game::new();
+        for (depth, nodes) in [1, 20, 400, 8_902, 197_281, 4_865_609]
+            .into_iter()
+            .enumerate()
+        {
+            assert_eq!(perft_game(&mut g, depth as u8), nodes);
+        }
+        assert_eq!(g, Game::new());
+    }
+
+    #[test]
+    /// Test the `Game`-based perft and its divide output on the Kiwipete position.
+    fn game_kiwipete() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let mut g = Game::from_fen(fen).unwrap();
+        assert_eq!(perft_game(&mut g, 3), 97_862);
+
+        let divide = perft_divide(&mut g, 3);
+        assert_eq!(divide.len(), 48);
+        assert_eq!(divide.iter().map(|&(_, n)| n).sum::<u64>(), 97_862);
+        assert_eq!(g, Game::from_fen(fen).unwrap());
+    }
+
     #[test]
     /// Test the perft values for the board starting position.
     fn start_position() {
@@ -316,4 +480,14 @@ mod perft {
             &[1, 46, 2_079, 89_890, 3_894_594, 164_075_551],
         );
     }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    /// Test that parallel perft counts exactly the same nodes as sequential perft.
+    fn parallel_perft_matches() {
+        let mut g = Game::new();
+        assert_eq!(perft_parallel(&g, 4), perft_game(&mut g, 4));
+        assert_eq!(perft_parallel(&g, 4), 197_281);
+        assert_eq!(perft_parallel(&g, 0), 1);
+    }
 }
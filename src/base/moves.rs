@@ -300,18 +300,139 @@ impl Move {
         Ok(s)
     }
 
-    #[allow(clippy::missing_panics_doc)]
     /// Given the string of an algebraic-notation move, get the `Move` which can be played.
+    /// This accepts exactly the same input as `from_san()`.
     ///
     /// # Errors
     ///
     /// This function will return an `Err` if `s` is not a valid algebraically-represented move in
     /// `b`.
     pub fn from_algebraic(s: &str, b: &Board) -> Result<Move, &'static str> {
-        get_moves::<{ GenMode::All }>(b)
+        Move::from_san(b, s)
+    }
+
+    #[must_use]
+    /// Construct the standard algebraic notation (SAN) for this move, as played on `board`.
+    /// This is the same as `to_algebraic()`, but for moves which are already known to be legal.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the move is illegal on `board`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Board, Move, Piece, Square};
+    ///
+    /// let b = Board::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    /// let m = Move::promoting(Square::E7, Square::E8, Piece::Queen);
+    /// assert_eq!(m.to_san(&b), "e8=Q+");
+    /// ```
+    pub fn to_san(self, board: &Board) -> String {
+        self.to_algebraic(board)
+            .expect("SAN requested for an illegal move")
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Parse a move written in standard algebraic notation (SAN) on `board`.
+    /// Parsing is lenient about the parts of SAN which carry no information: check and mate
+    /// markers and annotations such as `!?` are ignored, the capture marker `x` is optional, `=`
+    /// before a promotion is optional, and castling may be written with zeros.
+    /// However, the move must be legal, and it must identify exactly one legal move.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an `Err` if `s` is malformed, if no legal move matches it, or if
+    /// it matches more than one legal move.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Board, Move, Square};
+    ///
+    /// let b = Board::from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+    /// assert_eq!(
+    ///     Move::from_san(&b, "Nbd2"),
+    ///     Ok(Move::normal(Square::B1, Square::D2))
+    /// );
+    /// assert!(Move::from_san(&b, "Nd2").is_err());
+    /// ```
+    pub fn from_san(board: &Board, s: &str) -> Result<Move, &'static str> {
+        let s = s.trim().trim_end_matches(['+', '#', '!', '?']);
+
+        let castle_kingside = match s {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        };
+        if let Some(kingside) = castle_kingside {
+            return get_moves::<{ GenMode::All }>(board)
+                .into_iter()
+                .find(|m| {
+                    m.is_castle() && (m.to_square().file() > m.from_square().file()) == kingside
+                })
+                .ok_or("castling is not legal");
+        }
+
+        // split off the promotion, if there is one
+        let (body, promote_type) = match s.char_indices().last() {
+            Some((i, c)) if i > 0 && "NBRQ".contains(c) => {
+                let body = s[..i].strip_suffix('=').unwrap_or(&s[..i]);
+                (body, Piece::from_code(c))
+            }
+            Some(_) => (s, None),
+            None => return Err("empty SAN move"),
+        };
+
+        let (mover_type, body) = match body.chars().next() {
+            Some(c @ ('N' | 'B' | 'R' | 'Q' | 'K')) => (Piece::from_code(c).unwrap(), &body[1..]),
+            _ => (Piece::Pawn, body),
+        };
+        if promote_type.is_some() && mover_type != Piece::Pawn {
+            return Err("only pawns can promote");
+        }
+
+        if body.len() < 2 || !body.is_ascii() {
+            return Err("SAN move has no destination square");
+        }
+        let to_sq = Square::from_algebraic(&body[body.len() - 2..])?;
+        let prefix = &body[..body.len() - 2];
+        let (disambiguation, is_capture) = match prefix.strip_suffix('x') {
+            Some(p) => (p, true),
+            None => (prefix, false),
+        };
+
+        let mut from_file = None;
+        let mut from_rank = None;
+        for c in disambiguation.chars() {
+            match c {
+                'a'..='h' if from_file.is_none() && from_rank.is_none() => {
+                    from_file = Some(c as u8 - b'a');
+                }
+                '1'..='8' if from_rank.is_none() => from_rank = Some(c as u8 - b'1'),
+                _ => return Err("invalid disambiguation in SAN move"),
+            }
+        }
+
+        let mut candidates = get_moves::<{ GenMode::All }>(board)
             .into_iter()
-            .find(|m| m.to_algebraic(b).unwrap().as_str() == s)
-            .ok_or("not a legal algebraic move")
+            .filter(|&m| {
+                let from_sq = m.from_square();
+                !m.is_castle()
+                    && m.to_square() == to_sq
+                    && m.promote_type() == promote_type
+                    && board.type_at_square(from_sq) == Some(mover_type)
+                    && from_file.is_none_or(|f| from_sq.file() == f)
+                    && from_rank.is_none_or(|r| from_sq.rank() == r)
+                    && (!is_capture || board.is_move_capture(m))
+            });
+
+        let m = candidates.next().ok_or("no legal move matches SAN")?;
+        if candidates.next().is_some() {
+            return Err("ambiguous SAN move");
+        }
+
+        Ok(m)
     }
 
     #[inline(always)]
@@ -493,4 +614,79 @@ mod tests {
         assert_eq!(m.to_algebraic(&b).unwrap(), s);
         assert_eq!(Move::from_algebraic(s, &b).unwrap(), m);
     }
+
+    #[test]
+    /// Test that every legal move in a set of positions survives a round trip through SAN.
+    fn san_round_trip() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r1bq1b1r/ppp2kpp/2n5/3np3/2B5/8/PPPP1PPP/RNBQK2R w KQ - 0 7",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/3P4/1N6/PPP1PPPP/RNBQKB1R w KQkq - 1 5",
+            "k7/1P2P3/8/8/8/8/8/4K3 w - - 0 1",
+        ] {
+            let b = Board::from_fen(fen).unwrap();
+            for m in get_moves::<{ GenMode::All }>(&b) {
+                let san = m.to_san(&b);
+                assert_eq!(Move::from_san(&b, &san), Ok(m), "{san} in {fen}");
+            }
+        }
+    }
+
+    #[test]
+    /// Test that an ambiguous knight move is rejected, but each disambiguated form is accepted.
+    fn san_ambiguous_knight() {
+        let b = Board::from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+        assert_eq!(Move::from_san(&b, "Nd2"), Err("ambiguous SAN move"));
+        assert_eq!(
+            Move::from_san(&b, "Nbd2"),
+            Ok(Move::normal(Square::B1, Square::D2))
+        );
+        assert_eq!(
+            Move::from_san(&b, "Nfd2"),
+            Ok(Move::normal(Square::F3, Square::D2))
+        );
+        assert_eq!(Move::normal(Square::B1, Square::D2).to_san(&b), "Nbd2");
+    }
+
+    #[test]
+    /// Test that a promotion with check is generated and parsed, with or without its markers.
+    fn san_promotion_check() {
+        let b = Board::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let m = Move::promoting(Square::E7, Square::E8, Piece::Queen);
+        assert_eq!(m.to_san(&b), "e8=Q+");
+        for s in ["e8=Q+", "e8=Q", "e8Q"] {
+            assert_eq!(Move::from_san(&b, s), Ok(m));
+        }
+        assert_eq!(
+            Move::from_san(&b, "e8=N"),
+            Ok(Move::promoting(Square::E7, Square::E8, Piece::Knight))
+        );
+        // a promotion must say what it promotes to
+        assert!(Move::from_san(&b, "e8").is_err());
+    }
+
+    #[test]
+    /// Test that castling and illegal or malformed SAN are handled.
+    fn san_castle_and_illegal() {
+        let b = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        assert_eq!(
+            Move::from_san(&b, "O-O"),
+            Ok(Move::castling(Square::E1, Square::G1))
+        );
+        assert_eq!(
+            Move::from_san(&b, "0-0-0"),
+            Ok(Move::castling(Square::E1, Square::C1))
+        );
+
+        let start = Board::default();
+        assert_eq!(
+            Move::from_san(&start, "e5"),
+            Err("no legal move matches SAN")
+        );
+        assert!(Move::from_san(&start, "O-O").is_err());
+        assert!(Move::from_san(&start, "Nxf3").is_err());
+        assert!(Move::from_san(&start, "").is_err());
+        assert!(Move::from_san(&start, "Zz9").is_err());
+    }
 }
@@ -20,6 +20,8 @@
 
 use std::fmt::{Display, Formatter, Result};
 
+use super::Color;
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
 #[repr(u8)]
 /// The type of a piece.
@@ -88,6 +90,35 @@ impl Piece {
         }
     }
 
+    #[must_use]
+    /// Get the Unicode chess glyph for a piece of this type and color.
+    /// White pieces are drawn hollow and Black pieces are drawn solid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Color, Piece};
+    ///
+    /// assert_eq!(Piece::King.glyph(Color::White), '♔');
+    /// assert_eq!(Piece::King.glyph(Color::Black), '♚');
+    /// ```
+    pub const fn glyph(self, color: Color) -> char {
+        match (color, self) {
+            (Color::White, Piece::Knight) => '♘',
+            (Color::White, Piece::Bishop) => '♗',
+            (Color::White, Piece::Rook) => '♖',
+            (Color::White, Piece::Queen) => '♕',
+            (Color::White, Piece::Pawn) => '♙',
+            (Color::White, Piece::King) => '♔',
+            (Color::Black, Piece::Knight) => '♞',
+            (Color::Black, Piece::Bishop) => '♝',
+            (Color::Black, Piece::Rook) => '♜',
+            (Color::Black, Piece::Queen) => '♛',
+            (Color::Black, Piece::Pawn) => '♟',
+            (Color::Black, Piece::King) => '♚',
+        }
+    }
+
     #[must_use]
     /// Given a FEN character, convert it to a piece type.
     /// Must be uppercase.
@@ -109,3 +140,40 @@ impl Display for Piece {
         write!(f, "{}", self.code())
     }
 }
+
+// Tables indexed by `pt as usize` (such as the PST) rely on `Piece::ALL` listing every piece type
+// exactly once, in discriminant order.
+const _: () = {
+    assert!(Piece::ALL.len() == Piece::NUM);
+    let mut i = 0;
+    while i < Piece::NUM {
+        assert!(Piece::ALL[i] as usize == i);
+        i += 1;
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test that every piece type in `Piece::ALL` has a unique index in `0..Piece::NUM`.
+    fn all_indices_unique() {
+        let mut seen = [false; Piece::NUM];
+        for pt in Piece::ALL {
+            let idx = pt as usize;
+            assert!(idx < Piece::NUM);
+            assert!(!seen[idx]);
+            seen[idx] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    /// Test that every piece type survives a round trip through its FEN code.
+    fn code_round_trip() {
+        for pt in Piece::ALL {
+            assert_eq!(Piece::from_code(pt.code()), Some(pt));
+        }
+    }
+}
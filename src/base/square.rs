@@ -18,9 +18,11 @@
 
 //! Squares, which are positions on a board.
 
+#![warn(clippy::std_instead_of_core, clippy::std_instead_of_alloc)]
+
 use super::{Bitboard, Direction};
 
-use std::{
+use core::{
     convert::TryFrom,
     fmt::{Display, Formatter},
     mem::transmute,
@@ -199,6 +201,31 @@ impl Square {
         unsafe { transmute(self as u8 ^ 56) }
     }
 
+    #[inline(always)]
+    #[must_use]
+    /// Get the square `df` files and `dr` ranks away from this one.
+    /// Unlike adding a `Direction`, this will not wrap around the edges of the board: if the
+    /// resulting square would be off the board, this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::Square;
+    ///
+    /// assert_eq!(Square::E4.try_offset(1, -2), Some(Square::F2));
+    /// assert_eq!(Square::H4.try_offset(1, 0), None);
+    /// assert_eq!(Square::A1.try_offset(0, -1), None);
+    /// ```
+    pub fn try_offset(self, df: i8, dr: i8) -> Option<Square> {
+        let file = u8::try_from(i16::from(self.file()) + i16::from(df)).ok()?;
+        let rank = u8::try_from(i16::from(self.rank()) + i16::from(dr)).ok()?;
+        if file < 8 && rank < 8 {
+            Square::new(rank, file)
+        } else {
+            None
+        }
+    }
+
     #[allow(clippy::missing_panics_doc)]
     /// Convert an algebraic string (such as 'e7') to a square.
     ///
@@ -297,7 +324,7 @@ impl AddAssign<Direction> for Square {
 
 impl Display for Square {
     #[inline(always)]
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}{}", self.file_name(), self.rank() + 1)
     }
 }
@@ -360,4 +387,26 @@ mod tests {
         assert_eq!(Square::from_algebraic("e4"), Ok(Square::E4));
         assert_eq!(Square::from_algebraic("f7"), Ok(Square::F7));
     }
+
+    #[test]
+    /// Test that offsetting a square off any edge of the board yields `None` instead of wrapping.
+    fn offset_off_edge() {
+        assert_eq!(Square::A1.try_offset(-1, 0), None);
+        assert_eq!(Square::A1.try_offset(0, -1), None);
+        assert_eq!(Square::H8.try_offset(1, 0), None);
+        assert_eq!(Square::H8.try_offset(0, 1), None);
+        assert_eq!(Square::H3.try_offset(1, 1), None);
+        assert_eq!(Square::E4.try_offset(0, 5), None);
+        assert_eq!(Square::E4.try_offset(i8::MIN, i8::MAX), None);
+    }
+
+    #[test]
+    /// Test that offsets which stay on the board give the right square.
+    fn offset_on_board() {
+        assert_eq!(Square::E4.try_offset(0, 0), Some(Square::E4));
+        assert_eq!(Square::E4.try_offset(0, 1), Some(Square::E5));
+        assert_eq!(Square::E4.try_offset(-1, -1), Some(Square::D3));
+        assert_eq!(Square::A1.try_offset(7, 7), Some(Square::H8));
+        assert_eq!(Square::F6.try_offset(0, -1), Some(Square::F5));
+    }
 }
@@ -218,7 +218,7 @@ fn load_weights() -> Weights {
         let val = material::value(pt);
         weights
             .rule_values
-            .push((val.mg.float_val(), val.eg.float_val()));
+            .push((val.midgame().float_val(), val.endgame().float_val()));
     }
 
     for pt in Piece::ALL {
@@ -228,7 +228,7 @@ fn load_weights() -> Weights {
                 let score = PST[pt as usize][sq_idx];
                 weights
                     .rule_values
-                    .push((score.mg.float_val(), score.eg.float_val()));
+                    .push((score.midgame().float_val(), score.endgame().float_val()));
             }
         }
     }
@@ -264,7 +264,7 @@ fn print_weights(weights: &Weights) {
     println!("-----");
 
     // print PST
-    println!("pub const PST: Pst = unsafe {{ transmute([");
+    println!("pub const PST: Pst = from_pairs(&[");
     for pt in Piece::ALL {
         println!("    [ // {pt}");
         let pt_idx = offset + (64 * pt as usize);
@@ -281,7 +281,7 @@ fn print_weights(weights: &Weights) {
         }
         println!("    ],");
     }
-    println!("]) }};");
+    println!("]);");
 }
 
 #[allow(
@@ -0,0 +1,99 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Benchmarking for static evaluation.
+//!
+//! Every new evaluation rule makes evaluation a little slower.
+//! This module times full evaluations over a fixed set of positions, giving a stable
+//! microbenchmark for catching performance regressions.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::base::Board;
+
+use super::evaluate_board;
+
+/// A small, fixed set of positions covering the opening, middlegame, and endgame.
+pub const BENCH_FENS: [&str; 6] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r1bq1b1r/ppp2kpp/2n5/3np3/2B5/8/PPPP1PPP/RNBQK2R w KQ - 0 7",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "8/5k2/6p1/8/5PPP/8/pb3P2/6K1 w - - 0 37",
+];
+
+#[must_use]
+#[allow(clippy::missing_panics_doc)]
+/// Get the built-in set of benchmark positions.
+pub fn bench_positions() -> Vec<Board> {
+    BENCH_FENS
+        .iter()
+        .map(|fen| Board::from_fen(fen).unwrap())
+        .collect()
+}
+
+#[must_use]
+/// Time `iters` full evaluations of every position in `positions`, returning the total elapsed
+/// time.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::engine::evaluate::bench::{bench_evaluate, bench_positions, evals_per_second};
+///
+/// let positions = bench_positions();
+/// let elapsed = bench_evaluate(&positions, 10);
+/// let rate = evals_per_second(positions.len() as u64 * 10, elapsed);
+/// assert!(rate > 0.0);
+/// ```
+pub fn bench_evaluate(positions: &[Board], iters: u32) -> Duration {
+    let tic = Instant::now();
+    for _ in 0..iters {
+        for b in positions {
+            black_box(evaluate_board(black_box(b)));
+        }
+    }
+    tic.elapsed()
+}
+
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+/// Compute the number of evaluations performed per second, given the number of evaluations and the
+/// time they took.
+pub fn evals_per_second(num_evals: u64, elapsed: Duration) -> f64 {
+    num_evals as f64 / elapsed.as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test that the benchmark runs on the built-in positions and takes a nonzero amount of time.
+    fn bench_smoke() {
+        let positions = bench_positions();
+        assert_eq!(positions.len(), BENCH_FENS.len());
+        let elapsed = bench_evaluate(&positions, 100);
+        assert!(elapsed > Duration::ZERO);
+        assert!(evals_per_second(100 * positions.len() as u64, elapsed) > 0.);
+    }
+}
@@ -0,0 +1,174 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Evaluation caching.
+//!
+//! Static evaluation is expensive, and the same positions are often evaluated many times over the
+//! course of a search.
+//! An evaluation cache is a small direct-mapped table from Zobrist hashes to evaluations, so that
+//! repeated positions need not be evaluated from scratch.
+//! Unlike the transposition table, the evaluation cache is owned by a single thread, so it needs no
+//! unsafe code.
+//!
+//! Pawn structure changes far less often than the rest of the board, so its evaluation gets a
+//! separate pawn table keyed by a hash of only the pawns.
+
+use super::{Eval, Score};
+
+/// A cache of full evaluations, keyed by Zobrist hash.
+pub type EvalCache = Cache<Eval>;
+
+/// A cache of pawn structure evaluations, keyed by pawn hash (see `Board::pawn_hash`).
+pub type PawnTable = Cache<Score>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A fixed-size, direct-mapped cache of values, keyed by a hash.
+/// When two keys map to the same slot, the newer one always replaces the older.
+pub struct Cache<V> {
+    /// The slots of the cache.
+    /// Each occupied slot stores the full hash key alongside the value, so that a lookup never
+    /// returns the value of a different key in the same slot.
+    /// The length of `entries` is always a power of two.
+    entries: Box<[Option<(u64, V)>]>,
+    /// The mask for retrieving the index of a slot from a hash key.
+    mask: u64,
+}
+
+impl<V: Copy> Cache<V> {
+    #[must_use]
+    /// Construct a new, empty `Cache` with the given number of slots.
+    /// If `capacity` is not a power of two, it will be rounded down to one.
+    /// The cache will always have at least one slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::cache::EvalCache;
+    ///
+    /// assert_eq!(EvalCache::with_capacity(1000).capacity(), 512);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Cache<V> {
+        let len = if capacity.is_power_of_two() {
+            capacity
+        } else {
+            // round down to lower power of two
+            (capacity.next_power_of_two() >> 1).max(1)
+        };
+
+        Cache {
+            entries: vec![None; len].into_boxed_slice(),
+            mask: (len - 1) as u64,
+        }
+    }
+
+    #[must_use]
+    /// Get the number of slots in this cache.
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline(always)]
+    #[allow(clippy::cast_possible_truncation)]
+    /// Compute the index of the slot for a given key.
+    fn index_for(&self, hash_key: u64) -> usize {
+        (hash_key & self.mask) as usize
+    }
+
+    #[must_use]
+    /// Get the value stored for the given hash, if it is in the cache.
+    pub fn probe(&self, hash_key: u64) -> Option<V> {
+        match self.entries[self.index_for(hash_key)] {
+            Some((key, value)) if key == hash_key => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Store the value for the given hash, replacing whatever was in its slot.
+    pub fn store(&mut self, hash_key: u64, value: V) {
+        let idx = self.index_for(hash_key);
+        self.entries[idx] = Some((hash_key, value));
+    }
+
+    /// Remove every entry from this cache.
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test that an inserted evaluation can be retrieved.
+    fn insert_get() {
+        let mut cache = EvalCache::with_capacity(16);
+        assert_eq!(cache.probe(0x1234), None);
+        cache.store(0x1234, Eval::centipawns(55));
+        assert_eq!(cache.probe(0x1234), Some(Eval::centipawns(55)));
+    }
+
+    #[test]
+    /// Test that two keys mapping to the same slot never return each other's evaluations.
+    fn collision_checks_key() {
+        let mut cache = EvalCache::with_capacity(16);
+        cache.store(0x1234, Eval::centipawns(55));
+        // same low bits, so same slot
+        assert_eq!(cache.probe(0xF234), None);
+        cache.store(0xF234, Eval::centipawns(-20));
+        assert_eq!(cache.probe(0xF234), Some(Eval::centipawns(-20)));
+        assert_eq!(cache.probe(0x1234), None);
+    }
+
+    #[test]
+    /// Test that the cache never grows beyond its capacity.
+    fn size_respected() {
+        let mut cache = EvalCache::with_capacity(100);
+        assert_eq!(cache.capacity(), 64);
+        for key in 0..1000 {
+            cache.store(key, Eval::centipawns(1));
+        }
+        assert_eq!(cache.capacity(), 64);
+        assert_eq!(cache.entries.iter().filter(|e| e.is_some()).count(), 64);
+        cache.clear();
+        assert!(cache.entries.iter().all(Option::is_none));
+    }
+
+    #[test]
+    /// Test that a stored pawn score can be probed.
+    fn pawn_store_probe() {
+        let mut table = PawnTable::with_capacity(16);
+        assert_eq!(table.probe(0x1234), None);
+        table.store(0x1234, Score::centipawns(10, 20));
+        assert_eq!(table.probe(0x1234), Some(Score::centipawns(10, 20)));
+    }
+
+    #[test]
+    /// Test that a colliding pawn structure overwrites the old entry without being confused for it.
+    fn pawn_collision_overwrites() {
+        let mut table = PawnTable::with_capacity(16);
+        table.store(0x1234, Score::centipawns(10, 20));
+        // same low bits, so same slot
+        assert_eq!(table.probe(0xF234), None);
+        table.store(0xF234, Score::centipawns(-5, 0));
+        assert_eq!(table.probe(0xF234), Some(Score::centipawns(-5, 0)));
+        assert_eq!(table.probe(0x1234), None);
+        table.clear();
+        assert_eq!(table.probe(0xF234), None);
+    }
+}
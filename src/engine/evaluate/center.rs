@@ -0,0 +1,237 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Control of the center.
+//!
+//! The four central squares (d4, e4, d5, and e5) are the most contested squares in the opening.
+//! Pawns and minor pieces which stand on them get a small midgame bonus, independent of the
+//! piece-square tables, as does every piece (other than the king) which attacks them.
+//! Attacks on the rest of the extended center (c3 through f6) are worth a smaller bonus.
+//! In the endgame, the center matters far less, so this rule has no endgame weight.
+//!
+//! Space is measured the same way: safe squares in the central files behind a side's own pawns
+//! give its pieces room to maneuver, which only matters while there are pieces left to maneuver.
+
+use crate::{
+    base::{
+        movegen::{KNIGHT_MOVES, PAWN_ATTACKS},
+        Bitboard, Board, Color, Piece, Square, MAGIC,
+    },
+    engine::evaluate::Score,
+};
+
+/// The bonus for each pawn occupying a central square.
+pub const CENTER_PAWN: Score = Score::centipawns(12, 0);
+
+/// The bonus for each minor piece occupying a central square.
+pub const CENTER_MINOR: Score = Score::centipawns(6, 0);
+
+/// The bonus for each attack on a central square by a piece other than the king.
+pub const CENTER_ATTACK: Score = Score::centipawns(3, 0);
+
+/// The bonus for each attack on a square of the extended center, outside of the four central
+/// squares, by a piece other than the king.
+pub const EXTENDED_CENTER_ATTACK: Score = Score::centipawns(1, 0);
+
+/// The squares of the extended center which are not central squares.
+const CENTER_RING: Bitboard = Bitboard::from_squares(&[
+    Square::C3,
+    Square::D3,
+    Square::E3,
+    Square::F3,
+    Square::C4,
+    Square::F4,
+    Square::C5,
+    Square::F5,
+    Square::C6,
+    Square::D6,
+    Square::E6,
+    Square::F6,
+]);
+
+/// The bonus for each safe square of space, multiplied by the number of pieces (other than pawns
+/// and the king) the side has left.
+pub const SPACE: Score = Score::centipawns(1, 0);
+
+/// The squares which count toward space for each color: the C through F files, on ranks 2 through
+/// 4 from that color's point of view.
+const SPACE_MASK: [Bitboard; 2] = [
+    Bitboard::new(0x0000_0000_3C3C_3C00),
+    Bitboard::new(0x003C_3C3C_0000_0000),
+];
+
+#[must_use]
+/// Evaluate a position based on how well each side occupies and attacks the center.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{center::center_control, Eval};
+///
+/// let b = Board::from_fen("rnbqkbnr/pppppppp/8/8/3PP3/8/PPP2PPP/RNBQKBNR b KQkq - 0 2").unwrap();
+/// assert!(center_control(&b).midgame() > Eval::DRAW);
+/// ```
+pub fn center_control(board: &Board) -> Score {
+    center_for(board, Color::White) - center_for(board, Color::Black)
+}
+
+/// Get the center control bonus for one color, from that color's point of view.
+fn center_for(b: &Board, color: Color) -> Score {
+    let occupancy = b.occupancy();
+    let pawns = b[Piece::Pawn] & b[color];
+    let knights = b[Piece::Knight] & b[color];
+    let bishops = b[Piece::Bishop] & b[color];
+    let queens = b[Piece::Queen] & b[color];
+    let diagonals = bishops | queens;
+    let lines = (b[Piece::Rook] & b[color]) | queens;
+
+    let mut score = CENTER_PAWN * (pawns & Bitboard::CENTER).len();
+    score += CENTER_MINOR * ((knights | bishops) & Bitboard::CENTER).len();
+
+    for sq in pawns {
+        score += attack_bonus(PAWN_ATTACKS[color as usize][sq as usize]);
+    }
+    for sq in knights {
+        score += attack_bonus(KNIGHT_MOVES[sq as usize]);
+    }
+    for sq in diagonals {
+        score += attack_bonus(MAGIC.bishop_attacks(occupancy, sq));
+    }
+    for sq in lines {
+        score += attack_bonus(MAGIC.rook_attacks(occupancy, sq));
+    }
+
+    score
+}
+
+/// Get the bonus for a piece attacking the squares in `attacks`.
+fn attack_bonus(attacks: Bitboard) -> Score {
+    CENTER_ATTACK * (attacks & Bitboard::CENTER).len()
+        + EXTENDED_CENTER_ATTACK * (attacks & CENTER_RING).len()
+}
+
+#[must_use]
+/// Evaluate a position based on how much space each side has behind its pawns.
+/// Space is only given midgame weight, and is scaled by the number of pieces each side has left.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{center::space, Eval};
+///
+/// let b = Board::from_fen("rnbqkbnr/pppppppp/8/8/2PPP3/8/PP3PPP/RNBQKBNR b KQkq - 0 3").unwrap();
+/// assert!(space(&b).midgame() > Eval::DRAW);
+/// ```
+pub fn space(board: &Board) -> Score {
+    space_for(board, Color::White) - space_for(board, Color::Black)
+}
+
+/// Get the space bonus for one color, from that color's point of view.
+fn space_for(b: &Board, color: Color) -> Score {
+    let own_pawns = b[Piece::Pawn] & b[color];
+    let mut enemy_pawn_attacks = Bitboard::EMPTY;
+    for sq in b[Piece::Pawn] & b[!color] {
+        enemy_pawn_attacks |= PAWN_ATTACKS[!color as usize][sq as usize];
+    }
+
+    let safe = SPACE_MASK[color as usize] & !own_pawns & !enemy_pawn_attacks;
+    let behind = match color {
+        Color::White => own_pawns.south_fill(),
+        Color::Black => own_pawns.north_fill(),
+    };
+    let pieces = (b[color] & !(b[Piece::Pawn] | b[Piece::King])).len();
+
+    SPACE * ((safe & behind).len() * pieces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::evaluate::Eval;
+
+    #[test]
+    /// Test that a classical e4-d4 pawn center is worth more than a fianchetto without central
+    /// pawns.
+    fn classical_beats_fianchetto() {
+        let classical =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/3PP3/8/PPP2PPP/RNBQKBNR b KQkq - 0 2").unwrap();
+        let fianchetto =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/5NP1/PPPPPPBP/RNBQK2R b KQkq - 0 3").unwrap();
+        assert!(center_control(&classical).midgame() > center_control(&fianchetto).midgame());
+    }
+
+    #[test]
+    /// Test that center control has no weight in the endgame.
+    fn center_endgame() {
+        let b =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/3PP3/8/PPP2PPP/RNBQKBNR b KQkq - 0 2").unwrap();
+        assert_eq!(center_control(&b).blend(0.), Eval::DRAW);
+    }
+
+    #[test]
+    /// Test that the ring around the center is exactly the extended center minus the center.
+    fn ring_squares() {
+        assert_eq!(CENTER_RING, Bitboard::EXTENDED_CENTER & !Bitboard::CENTER);
+    }
+
+    #[test]
+    /// Test that the starting position is balanced.
+    fn start_balanced() {
+        assert_eq!(center_control(&Board::new()), Score::DRAW);
+        assert_eq!(space(&Board::new()), Score::DRAW);
+    }
+
+    #[test]
+    /// Test that a broad pawn center gains more space than a cramped setup.
+    fn broad_center_more_space() {
+        let broad =
+            Board::from_fen("r1bqkb1r/pppppppp/2n2n2/8/2PPPP2/8/PP4PP/RNBQKBNR w KQkq - 0 1")
+                .unwrap();
+        let cramped =
+            Board::from_fen("r1bqkb1r/pppppppp/2n2n2/8/8/3PP3/PPP2PPP/RNBQKBNR w KQkq - 0 1")
+                .unwrap();
+        assert!(space(&broad).midgame() > space(&cramped).midgame());
+        assert!(space(&cramped).midgame() > Eval::DRAW);
+        assert_eq!(space(&broad).blend(0.), Eval::DRAW);
+    }
+
+    #[test]
+    /// Test that space is worth less once pieces have been traded.
+    fn space_scales_with_pieces() {
+        let full =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/2PPPP2/8/PP4PP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let traded = Board::from_fen("4k3/pppppppp/8/8/2PPPP2/8/PP4PP/R3K3 w - - 0 1").unwrap();
+        assert!(space(&full).midgame() > space(&traded).midgame());
+    }
+
+    #[test]
+    /// Test that a classical center with developed knights controls more of the center than a
+    /// setup which only develops on the flanks.
+    fn center_beats_flank() {
+        let center =
+            Board::from_fen("r1bqkb1r/pppppppp/2n2n2/8/3PP3/2N2N2/PPP2PPP/R1BQKB1R w KQkq - 0 1")
+                .unwrap();
+        let flank =
+            Board::from_fen("r1bqkb1r/pppppppp/2n2n2/8/P6P/N6N/1PPPPPP1/R1BQKB1R w KQkq - 0 1")
+                .unwrap();
+        assert!(center_control(&center).midgame() > center_control(&flank).midgame());
+        assert!(center_control(&flank).midgame() < Eval::DRAW);
+    }
+}
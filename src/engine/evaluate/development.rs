@@ -0,0 +1,98 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Opening development rules.
+//!
+//! Bringing the queen out before the minor pieces is a classic opening error: the queen is easily
+//! harassed by developing moves, and the minor pieces left at home do nothing.
+//! This rule only applies in the midgame; by the endgame nobody cares where the knights started.
+
+use crate::{
+    base::{Bitboard, Board, Color, Piece, Square},
+    engine::evaluate::Score,
+};
+
+/// The penalty for a queen which has left home while two or more minor pieces are undeveloped.
+pub const EARLY_QUEEN: Score = Score::centipawns(-20, 0);
+
+/// The starting squares of the minor pieces (b, c, f, and g-files on the back rank), indexed by
+/// color.
+const MINOR_HOMES: [Bitboard; 2] = [
+    Bitboard::new(0x0000_0000_0000_0066),
+    Bitboard::new(0x6600_0000_0000_0000),
+];
+
+/// The starting squares of the queens, indexed by color.
+const QUEEN_HOMES: [Square; 2] = [Square::D1, Square::D8];
+
+#[must_use]
+/// Evaluate a position based on how well each side has developed.
+pub fn evaluate(b: &Board) -> Score {
+    early_queen(b, Color::White) - early_queen(b, Color::Black)
+}
+
+/// Get the early-queen penalty for one color, from that color's point of view.
+fn early_queen(b: &Board, color: Color) -> Score {
+    let queens = b[Piece::Queen] & b[color];
+    let undeveloped =
+        (b[Piece::Knight] | b[Piece::Bishop]) & b[color] & MINOR_HOMES[color as usize];
+
+    if !queens.is_empty()
+        && !queens.contains(QUEEN_HOMES[color as usize])
+        && undeveloped.more_than_one()
+    {
+        EARLY_QUEEN
+    } else {
+        Score::DRAW
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::evaluate::Eval;
+
+    #[test]
+    /// Test that bringing the queen out to h5 before developing is worse than bringing it out after
+    /// the minor pieces are out.
+    fn early_queen_worse() {
+        let early =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p2Q/4P3/8/PPPP1PPP/RNB1KBNR b KQkq - 1 2")
+                .unwrap();
+        let developed =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p2Q/2B1P3/2N2N2/PPPP1PPP/R1B1K2R w KQkq - 0 1")
+                .unwrap();
+        assert_eq!(evaluate(&early), EARLY_QUEEN);
+        assert_eq!(evaluate(&developed), Score::DRAW);
+        assert!(evaluate(&early).blend(1.) < evaluate(&developed).blend(1.));
+    }
+
+    #[test]
+    /// Test that the early queen penalty has no effect in the endgame.
+    fn early_queen_endgame() {
+        let b = Board::from_fen("rnbqkbnr/pppp1ppp/8/4p2Q/4P3/8/PPPP1PPP/RNB1KBNR b KQkq - 1 2")
+            .unwrap();
+        assert_eq!(evaluate(&b).blend(0.), Eval::DRAW);
+    }
+
+    #[test]
+    /// Test that the starting position is balanced.
+    fn start_balanced() {
+        assert_eq!(evaluate(&Board::new()), Score::DRAW);
+    }
+}
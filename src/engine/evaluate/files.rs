@@ -0,0 +1,112 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Classification of files by their pawns.
+//!
+//! Many evaluation rules (such as rooks on open files or king safety) care about which files have
+//! pawns on them.
+//! Rather than have every rule recompute this, the classification of all eight files can be
+//! computed once from the pawn bitboards and shared.
+
+use crate::base::{Bitboard, Board, Color, Piece};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The status of a file, based on which colors have pawns on it.
+pub enum FileStatus {
+    /// There are no pawns on the file.
+    Open,
+    /// The file is half-open for White: there are Black pawns on it, but no White pawns.
+    HalfOpenWhite,
+    /// The file is half-open for Black: there are White pawns on it, but no Black pawns.
+    HalfOpenBlack,
+    /// There are pawns of both colors on the file.
+    Closed,
+}
+
+#[must_use]
+/// Classify every file on the board, from the A-file to the H-file.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::files::{file_status, FileStatus};
+///
+/// assert_eq!(file_status(&Board::new()), [FileStatus::Closed; 8]);
+/// ```
+pub fn file_status(board: &Board) -> [FileStatus; 8] {
+    let white_pawns = board[Piece::Pawn] & board[Color::White];
+    let black_pawns = board[Piece::Pawn] & board[Color::Black];
+
+    Bitboard::FILES.map(|file| {
+        match (
+            (file & white_pawns).is_empty(),
+            (file & black_pawns).is_empty(),
+        ) {
+            (true, true) => FileStatus::Open,
+            (true, false) => FileStatus::HalfOpenWhite,
+            (false, true) => FileStatus::HalfOpenBlack,
+            (false, false) => FileStatus::Closed,
+        }
+    })
+}
+
+impl FileStatus {
+    #[must_use]
+    /// Determine whether a file with this status has no pawns of the given color, i.e. whether it
+    /// is open or half-open from that color's point of view.
+    pub const fn is_open_for(self, color: Color) -> bool {
+        matches!(
+            (self, color),
+            (FileStatus::Open, _)
+                | (FileStatus::HalfOpenWhite, Color::White)
+                | (FileStatus::HalfOpenBlack, Color::Black)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test the classification of an open file, files with pawns of only one color, and files with
+    /// pawns of both colors.
+    fn classify_files() {
+        // d-file is fully open, c-file has only a White pawn, e-file has only a Black pawn
+        let b = Board::from_fen("4k3/pp3ppp/4p3/8/2P5/8/PP3PPP/4K3 w - - 0 1").unwrap();
+        let status = file_status(&b);
+        assert_eq!(status[3], FileStatus::Open);
+        assert_eq!(status[2], FileStatus::HalfOpenBlack);
+        assert_eq!(status[4], FileStatus::HalfOpenWhite);
+        assert_eq!(status[0], FileStatus::Closed);
+        assert_eq!(status[7], FileStatus::Closed);
+    }
+
+    #[test]
+    /// Test that openness for a color agrees with the classification.
+    fn open_for_color() {
+        assert!(FileStatus::Open.is_open_for(Color::White));
+        assert!(FileStatus::Open.is_open_for(Color::Black));
+        assert!(FileStatus::HalfOpenWhite.is_open_for(Color::White));
+        assert!(!FileStatus::HalfOpenWhite.is_open_for(Color::Black));
+        assert!(!FileStatus::HalfOpenBlack.is_open_for(Color::White));
+        assert!(FileStatus::HalfOpenBlack.is_open_for(Color::Black));
+        assert!(!FileStatus::Closed.is_open_for(Color::White));
+    }
+}
@@ -0,0 +1,260 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! King safety.
+//!
+//! In the midgame, the king wants to hide in a corner behind its pawns.
+//! A king stuck in the center is a long-term liability even when nothing is attacking it yet,
+//! since the center is where lines open up first.
+//! The danger grows with the amount of enemy material left to attack with.
+//! A king is also safer behind an intact shield of pawns on the files around it.
+//! Conversely, pieces close to the enemy king are rewarded, since they are in position to join an
+//! attack.
+
+use crate::{
+    base::{Board, Color, Piece, Square},
+    engine::evaluate::{material, Eval, Score},
+};
+
+/// The midgame penalty for each step the king is away from a castled square, when the enemy has
+/// all of its pieces.
+pub const CENTRAL_KING: Eval = Eval::centipawns(-12);
+
+/// The penalty for each file next to (or on) the king which has no shield pawn in the two ranks in
+/// front of the king.
+pub const SHIELD_MISSING: Score = Score::centipawns(-22, 0);
+
+/// The penalty for each file next to (or on) the king whose shield pawn has advanced two ranks in
+/// front of the king instead of one.
+pub const SHIELD_ADVANCED: Score = Score::centipawns(-9, 0);
+
+/// The midgame bonus for each step closer than the far side of the board that a piece stands to
+/// the enemy king, indexed by piece type.
+/// Pawns and kings are not counted.
+pub const TROPISM: [Score; Piece::NUM] = [
+    Score::centipawns(1, 0), // knight
+    Score::centipawns(1, 0), // bishop
+    Score::centipawns(2, 0), // rook
+    Score::centipawns(3, 0), // queen
+    Score::DRAW,             // pawn
+    Score::DRAW,             // king
+];
+
+/// The squares that a king lands on after castling, indexed by color.
+const CASTLED_SQUARES: [[Square; 2]; 2] = [[Square::G1, Square::C1], [Square::G8, Square::C8]];
+
+/// The midgame value of one side's starting non-pawn material.
+const FULL_NPM: i16 = 2
+    * (material::value(Piece::Knight).midgame().centipawn_val()
+        + material::value(Piece::Bishop).midgame().centipawn_val()
+        + material::value(Piece::Rook).midgame().centipawn_val())
+    + material::value(Piece::Queen).midgame().centipawn_val();
+
+#[must_use]
+/// Evaluate a position based on the safety of each king.
+pub fn evaluate(b: &Board) -> Score {
+    central_king(b, Color::White) - central_king(b, Color::Black) + king_safety(b) + tropism(b)
+}
+
+#[must_use]
+/// Evaluate the pawn shield in front of each king.
+/// For each of the three files around a king, a pawn directly in front of the king is ideal, a
+/// pawn two ranks in front is worse, and no pawn at all is worst.
+/// Pawn shields only matter in the midgame.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{king::king_safety, Eval};
+///
+/// // White's g-pawn has advanced to g4, leaving a hole in front of the king
+/// let b = Board::from_fen("6k1/5ppp/8/8/6P1/8/5P1P/6K1 w - - 0 1").unwrap();
+/// assert!(king_safety(&b).midgame() < Eval::DRAW);
+/// ```
+pub fn king_safety(board: &Board) -> Score {
+    shield(board, Color::White) - shield(board, Color::Black)
+}
+
+/// Get the pawn shield penalty for the king of `color`, from that color's point of view.
+fn shield(b: &Board, color: Color) -> Score {
+    let king_sq = b.king_sqs[color as usize];
+    let forward = match color {
+        Color::White => 1,
+        Color::Black => -1,
+    };
+    let pawns = b[Piece::Pawn] & b[color];
+
+    let mut score = Score::DRAW;
+    for df in -1..=1 {
+        // a king on the far edge of the board has no room for a shield
+        let Some(near) = king_sq.try_offset(df, forward) else {
+            continue;
+        };
+        if pawns.contains(near) {
+            continue;
+        }
+        let far = king_sq.try_offset(df, 2 * forward);
+        score += if far.is_some_and(|sq| pawns.contains(sq)) {
+            SHIELD_ADVANCED
+        } else {
+            SHIELD_MISSING
+        };
+    }
+
+    score
+}
+
+#[must_use]
+/// Evaluate how close each side's pieces are to the enemy king.
+/// Each piece gets a bonus for every step its Chebyshev distance to the enemy king is short of the
+/// width of the board, weighted by its type.
+/// Tropism only matters in the midgame, when there is enough material to mount an attack.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{king::tropism, Eval};
+///
+/// let b = Board::from_fen("6k1/5ppp/5Q2/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+/// assert!(tropism(&b).midgame() > Eval::DRAW);
+/// ```
+pub fn tropism(board: &Board) -> Score {
+    tropism_for(board, Color::White) - tropism_for(board, Color::Black)
+}
+
+/// Get the tropism bonus for the pieces of `color`, from that color's point of view.
+fn tropism_for(b: &Board, color: Color) -> Score {
+    let enemy_king = b.king_sqs[!color as usize];
+    let mut score = Score::DRAW;
+    for pt in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+        for sq in b[pt] & b[color] {
+            score += TROPISM[pt as usize] * (7 - sq.chebyshev_to(enemy_king));
+        }
+    }
+
+    score
+}
+
+#[must_use]
+/// Get the penalty for the king of `color` being away from a castled square, from that color's
+/// point of view.
+/// The penalty is scaled by the amount of non-pawn material the enemy has left, and only applies
+/// in the midgame.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, Color};
+/// use tomato::engine::evaluate::{king::central_king, Eval};
+///
+/// let b = Board::new();
+/// assert!(central_king(&b, Color::White).midgame() < Eval::DRAW);
+/// assert_eq!(central_king(&b, Color::White).endgame(), Eval::DRAW);
+/// ```
+pub fn central_king(b: &Board, color: Color) -> Score {
+    let king_sq = b.king_sqs[color as usize];
+    let dist = CASTLED_SQUARES[color as usize]
+        .iter()
+        .map(|&sq| king_sq.chebyshev_to(sq))
+        .min()
+        .unwrap_or(0);
+
+    let mut enemy_npm = Eval::DRAW;
+    for pt in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+        enemy_npm += material::value(pt).midgame() * (b[pt] & b[!color]).len();
+    }
+    let danger = (f32::from(enemy_npm.centipawn_val()) / f32::from(FULL_NPM)).min(1.);
+
+    Score::new(CENTRAL_KING * (f32::from(dist) * danger), Eval::DRAW)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test that an uncastled king in the center with all the pieces on the board is worse than a
+    /// castled one.
+    fn uncastled_worse() {
+        let castled =
+            Board::from_fen("rnbq1rk1/pppppppp/8/8/8/8/PPPPPPPP/RNBQ1RK1 w - - 0 1").unwrap();
+        let central =
+            Board::from_fen("rnbq1rk1/pppppppp/8/8/8/8/PPPPPPPP/RNBQK2R w K - 0 1").unwrap();
+        assert_eq!(evaluate(&castled), Score::DRAW);
+        assert!(evaluate(&central).blend(1.) < evaluate(&castled).blend(1.));
+    }
+
+    #[test]
+    /// Test that a castled king with an intact pawn shield is safer than one whose shield pawns
+    /// have advanced or disappeared.
+    fn intact_shield_safer() {
+        let intact = Board::from_fen("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+        let advanced = Board::from_fen("6k1/5ppp/8/8/8/6P1/5P1P/6K1 w - - 0 1").unwrap();
+        let pushed = Board::from_fen("6k1/5ppp/8/8/6P1/8/5P1P/6K1 w - - 0 1").unwrap();
+        assert_eq!(king_safety(&intact), Score::DRAW);
+        assert_eq!(king_safety(&advanced), SHIELD_ADVANCED);
+        assert_eq!(king_safety(&pushed), SHIELD_MISSING);
+        assert_eq!(king_safety(&pushed).blend(0.), Eval::DRAW);
+    }
+
+    #[test]
+    /// Test that the pawn shield works the same way for Black.
+    fn black_shield() {
+        let b = Board::from_fen("1k6/p1p5/8/8/8/8/PPP5/1K6 w - - 0 1").unwrap();
+        assert_eq!(king_safety(&b), Score::DRAW - SHIELD_MISSING);
+    }
+
+    #[test]
+    /// Test that a king one step from the far edge is still checked for a shield in front of it,
+    /// while a king on the far edge has no shield squares at all.
+    fn shield_near_edge() {
+        let b = Board::from_fen("8/6K1/8/8/8/8/8/k7 w - - 0 1").unwrap();
+        assert_eq!(shield(&b, Color::White), SHIELD_MISSING * 3u8);
+        assert_eq!(shield(&b, Color::Black), Score::DRAW);
+    }
+
+    #[test]
+    /// Test that a queen near the enemy king scores higher for the attacker than one far away.
+    fn queen_near_king() {
+        let near = Board::from_fen("6k1/5ppp/5Q2/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+        let far = Board::from_fen("6k1/5ppp/8/8/8/8/Q4PPP/6K1 w - - 0 1").unwrap();
+        assert_eq!(tropism(&near), TROPISM[Piece::Queen as usize] * 5u8);
+        assert_eq!(tropism(&far), TROPISM[Piece::Queen as usize] * 1u8);
+        assert!(tropism(&near).midgame() > tropism(&far).midgame());
+        assert_eq!(tropism(&near).blend(0.), Eval::DRAW);
+
+        // the same holds for Black
+        let black_near = Board::from_fen("6k1/5ppp/8/8/8/5q2/5PPP/6K1 w - - 0 1").unwrap();
+        assert_eq!(tropism(&black_near), Score::DRAW - tropism(&near));
+    }
+
+    #[test]
+    /// Test that the central king penalty shrinks as the enemy's material comes off the board, and
+    /// vanishes in the endgame.
+    fn penalty_fades() {
+        let full = Board::from_fen("rnbq1rk1/pppppppp/8/8/8/8/PPPPPPPP/RNBQK2R w K - 0 1").unwrap();
+        let sparse = Board::from_fen("5rk1/pppppppp/8/8/8/8/PPPPPPPP/RNBQK2R w K - 0 1").unwrap();
+        let full_penalty = central_king(&full, Color::White);
+        let sparse_penalty = central_king(&sparse, Color::White);
+        assert!(full_penalty.midgame() < sparse_penalty.midgame());
+        assert!(sparse_penalty.midgame() < Eval::DRAW);
+        assert_eq!(full_penalty.blend(0.), Eval::DRAW);
+    }
+}
@@ -27,21 +27,40 @@
 //! However, any chess player worth their salt might tell you that bishops are a little more
 //! valuable than knights.
 //! Empirically, the engine agrees.
+//! A pair of bishops is worth more still, since together they cover squares of both colors.
 
 use crate::{
     base::{Board, Color, Move, Piece},
     engine::evaluate::Score,
 };
 
+/// The value of a knight.
+pub const KNIGHT_VALUE: Score = Score::centipawns(273, 250);
+
+/// The value of a bishop.
+pub const BISHOP_VALUE: Score = Score::centipawns(287, 264);
+
+/// The value of a rook.
+pub const ROOK_VALUE: Score = Score::centipawns(439, 408);
+
+/// The value of a queen.
+pub const QUEEN_VALUE: Score = Score::centipawns(917, 816);
+
+/// The value of a pawn.
+pub const PAWN_VALUE: Score = Score::centipawns(92, 95);
+
+/// The bonus for having two or more bishops.
+pub const BISHOP_PAIR: Score = Score::centipawns(20, 35);
+
 #[must_use]
 /// Get the value of one piece by its type.
 pub const fn value(pt: Piece) -> Score {
     match pt {
-        Piece::Knight => Score::centipawns(273, 250),
-        Piece::Bishop => Score::centipawns(287, 264),
-        Piece::Rook => Score::centipawns(439, 408),
-        Piece::Queen => Score::centipawns(917, 816),
-        Piece::Pawn => Score::centipawns(92, 95),
+        Piece::Knight => KNIGHT_VALUE,
+        Piece::Bishop => BISHOP_VALUE,
+        Piece::Rook => ROOK_VALUE,
+        Piece::Queen => QUEEN_VALUE,
+        Piece::Pawn => PAWN_VALUE,
         Piece::King => Score::DRAW,
     }
 }
@@ -70,7 +89,24 @@ pub fn delta(b: &Board, m: Move) -> Score {
 
 #[must_use]
 #[allow(clippy::cast_possible_wrap)]
-/// Evaluate a position solely by the amount of material available.
+/// Evaluate a position solely by the amount of material available, with no positional terms.
+/// The kings are not counted.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{
+///     material::{evaluate, QUEEN_VALUE},
+///     Score,
+/// };
+///
+/// assert_eq!(evaluate(&Board::new()), Score::DRAW);
+///
+/// let no_queen =
+///     Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNB1KBNR w KQkq - 0 1").unwrap();
+/// assert_eq!(evaluate(&no_queen), Score::DRAW - QUEEN_VALUE);
+/// ```
 pub fn evaluate(b: &Board) -> Score {
     let mut score = Score::centipawns(0, 0);
 
@@ -89,6 +125,30 @@ pub fn evaluate(b: &Board) -> Score {
     score
 }
 
+#[must_use]
+/// Evaluate the bishop pair for each side.
+/// This is kept separate from `evaluate` so that `delta` only has to account for raw material.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{material::bishop_pair, Score};
+///
+/// // both sides have their bishops
+/// assert_eq!(bishop_pair(&Board::new()), Score::DRAW);
+/// ```
+pub fn bishop_pair(board: &Board) -> Score {
+    let mut score = Score::DRAW;
+    if (board[Piece::Bishop] & board[Color::White]).len() >= 2 {
+        score += BISHOP_PAIR;
+    }
+    if (board[Piece::Bishop] & board[Color::Black]).len() >= 2 {
+        score -= BISHOP_PAIR;
+    }
+    score
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,6 +169,31 @@ mod tests {
         }
     }
 
+    #[test]
+    /// Test that having the bishop pair is better than having a bishop and a knight.
+    fn bishop_pair_bonus() {
+        let pair =
+            Board::from_fen("r1bqkb1r/pppppppp/8/8/8/8/PPPPPPPP/R1BQKB1R w KQkq - 0 1").unwrap();
+        let split =
+            Board::from_fen("r1bqkb1r/pppppppp/8/8/8/8/PPPPPPPP/R1BQKN1R w KQkq - 0 1").unwrap();
+        assert_eq!(bishop_pair(&pair), Score::DRAW);
+        assert_eq!(bishop_pair(&split), Score::DRAW - BISHOP_PAIR);
+        assert!(
+            (evaluate(&pair) + bishop_pair(&pair)).blend(0.5)
+                > (evaluate(&split) + bishop_pair(&split)).blend(0.5)
+        );
+    }
+
+    #[test]
+    /// Test that each piece's value is counted once per piece, for both colors.
+    fn counts_pieces() {
+        assert_eq!(evaluate(&Board::new()), Score::DRAW);
+
+        // White is missing its queen, Black is missing a knight and two pawns
+        let b = Board::from_fen("r1bqkbnr/pppppp2/8/8/8/8/PPPPPPPP/RNB1KBNR w KQkq - 0 1").unwrap();
+        assert_eq!(evaluate(&b), KNIGHT_VALUE + PAWN_VALUE * 2u8 - QUEEN_VALUE);
+    }
+
     #[test]
     fn delta_captures() {
         delta_helper("r1bq1b1r/ppp2kpp/2n5/3n4/2BPp3/2P5/PP3PPP/RNBQK2R b KQ d3 0 8");
@@ -0,0 +1,449 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Piece mobility.
+//!
+//! A piece which can move to many squares is more useful than one which is hemmed in.
+//! Each piece is rewarded (or penalized) by how far its number of target squares is from the
+//! number a piece of its type typically has.
+//! Safe mobility additionally ignores squares attacked by enemy pawns, since a piece which moves
+//! there can simply be chased away or traded for a pawn.
+
+use crate::{
+    base::{
+        movegen::{piece_attacks, KNIGHT_MOVES, PAWN_ATTACKS},
+        Bitboard, Board, Color, Move, Piece, Square,
+    },
+    engine::evaluate::Score,
+};
+
+/// The value of each additional target square, indexed by piece type.
+/// Pawns and kings are not evaluated for mobility.
+pub const MOBILITY: [Score; Piece::NUM] = [
+    Score::centipawns(4, 4), // N
+    Score::centipawns(5, 5), // B
+    Score::centipawns(2, 4), // R
+    Score::centipawns(1, 2), // Q
+    Score::centipawns(0, 0), // P
+    Score::centipawns(0, 0), // K
+];
+
+/// The typical number of target squares for a piece of each type.
+/// A piece with exactly this many targets gets no mobility bonus.
+const MOBILITY_CENTER: [i8; Piece::NUM] = [4, 6, 6, 12, 0, 0];
+
+#[must_use]
+/// Evaluate a position based on the mobility of each side's pieces.
+/// A piece's targets are the squares it attacks inside its side's mobility area.
+pub fn evaluate(b: &Board) -> Score {
+    mobility(
+        b,
+        mobility_area(b, Color::White),
+        mobility_area(b, Color::Black),
+    )
+}
+
+#[must_use]
+/// Evaluate a position based on the safe mobility of each side's pieces.
+/// This is the same as `evaluate`, except that squares attacked by enemy pawns are not counted as
+/// targets.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::mobility::{evaluate, evaluate_safe};
+///
+/// // the black pawns on e7 and h6 take away three of the knight's squares
+/// let b = Board::from_fen("4k3/4p3/7p/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+/// assert!(evaluate_safe(&b).midgame() < evaluate(&b).midgame());
+/// ```
+pub fn evaluate_safe(b: &Board) -> Score {
+    mobility(
+        b,
+        mobility_area(b, Color::White) & !pawn_attacks(b, Color::Black),
+        mobility_area(b, Color::Black) & !pawn_attacks(b, Color::White),
+    )
+}
+
+#[must_use]
+/// Get the mobility area for the pieces of `color`: the squares which count as targets for their
+/// mobility.
+/// A side's own pawns and king are excluded, since its pieces can never move there.
+/// Squares occupied by its other pieces still count, since a piece defending a friend is still
+/// doing useful work.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, Color, Square};
+/// use tomato::engine::evaluate::mobility::mobility_area;
+///
+/// let area = mobility_area(&Board::new(), Color::White);
+/// assert!(!area.contains(Square::E1));
+/// assert!(!area.contains(Square::E2));
+/// assert!(area.contains(Square::D1));
+/// assert!(area.contains(Square::E7));
+/// ```
+pub fn mobility_area(b: &Board, color: Color) -> Bitboard {
+    !(b[color] & (b[Piece::Pawn] | b[Piece::King]))
+}
+
+#[must_use]
+#[allow(clippy::missing_panics_doc)]
+/// Get the difference in mobility score which would be generated by making the move `m` on
+/// `board`.
+/// Like `pst::delta`, the result reflects how the position improves for the player making the
+/// move, independent of whether the player is White or Black.
+///
+/// Only the pieces whose targets could have changed are recomputed: those which move or are
+/// captured, and those whose rays toward a square whose contents change are not blocked by a piece
+/// which stays put.
+/// The board is never modified; the position after the move is described only by its occupancy
+/// and the mobility area of each side.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, Move, Square};
+/// use tomato::engine::evaluate::{mobility::delta, Eval};
+///
+/// // developing a knight increases mobility
+/// let b = Board::new();
+/// assert!(delta(&b, Move::normal(Square::G1, Square::F3)).midgame() > Eval::DRAW);
+/// ```
+pub fn delta(board: &Board, m: Move) -> Score {
+    let player = board.player;
+    let opponent = !player;
+    let from_sq = m.from_square();
+    let to_sq = m.to_square();
+    let mover = board.type_at_square(from_sq).unwrap();
+
+    // every piece which leaves a square, and every piece which arrives on one
+    let mut lifted = [(from_sq, mover, player); 2];
+    let mut n_lifted = 1;
+    let mut dropped = [(to_sq, m.promote_type().unwrap_or(mover), player); 2];
+    let mut n_dropped = 1;
+    if m.is_castle() {
+        // in Chess960, the king may land on its own rook's square, so this comes before captures
+        if let Some((rook_from_sq, rook_to_sq)) = board.castle_rook_squares(m) {
+            lifted[1] = (rook_from_sq, Piece::Rook, player);
+            dropped[1] = (rook_to_sq, Piece::Rook, player);
+            n_lifted = 2;
+            n_dropped = 2;
+        }
+    } else if m.is_en_passant() {
+        let capturee_sq = Square::new(from_sq.rank(), to_sq.file()).unwrap();
+        lifted[1] = (capturee_sq, Piece::Pawn, opponent);
+        n_lifted = 2;
+    } else if let Some(capturee) = board.type_at_square(to_sq) {
+        lifted[1] = (to_sq, capturee, opponent);
+        n_lifted = 2;
+    }
+    let lifted = &lifted[..n_lifted];
+    let dropped = &dropped[..n_dropped];
+
+    let lifted_sqs: Bitboard = lifted.iter().map(|&(sq, _, _)| sq).collect();
+    let dropped_sqs: Bitboard = dropped.iter().map(|&(sq, _, _)| sq).collect();
+    let changed = lifted_sqs | dropped_sqs;
+    let occupancy = board.occupancy();
+    let occupancy_after = (occupancy & !lifted_sqs) | dropped_sqs;
+
+    let area = [
+        mobility_area(board, Color::White),
+        mobility_area(board, Color::Black),
+    ];
+    // the change in mobility for each color, from that color's point of view
+    let mut gained = [Score::DRAW; 2];
+
+    // only pawns and kings shape the mobility area, and they have no mobility of their own
+    let fixed = board[Piece::Pawn] | board[Piece::King];
+    let mut fixed_after = [fixed & board[Color::White], fixed & board[Color::Black]];
+    for &(sq, pt, color) in lifted {
+        if matches!(pt, Piece::Pawn | Piece::King) {
+            fixed_after[color as usize] &= !Bitboard::from(sq);
+        } else {
+            gained[color as usize] -= for_piece(
+                pt,
+                piece_attacks(pt, color, sq, occupancy) & area[color as usize],
+            );
+        }
+    }
+    for &(sq, pt, color) in dropped {
+        if matches!(pt, Piece::Pawn | Piece::King) {
+            fixed_after[color as usize] |= Bitboard::from(sq);
+        }
+    }
+    let area_after = [!fixed_after[0], !fixed_after[1]];
+    for &(sq, pt, color) in dropped {
+        if !matches!(pt, Piece::Pawn | Piece::King) {
+            gained[color as usize] += for_piece(
+                pt,
+                piece_attacks(pt, color, sq, occupancy_after) & area_after[color as usize],
+            );
+        }
+    }
+
+    // a square occupied both before and after the move blocks a ray in both positions
+    let stable = occupancy & occupancy_after;
+    for pt in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+        for sq in board[pt] & !changed {
+            if !reaches(pt, sq, changed, stable) {
+                continue;
+            }
+            let color = if board[Color::White].contains(sq) {
+                Color::White
+            } else {
+                Color::Black
+            };
+            gained[color as usize] += for_piece(
+                pt,
+                piece_attacks(pt, color, sq, occupancy_after) & area_after[color as usize],
+            ) - for_piece(
+                pt,
+                piece_attacks(pt, color, sq, occupancy) & area[color as usize],
+            );
+        }
+    }
+
+    gained[player as usize] - gained[opponent as usize]
+}
+
+/// Determine whether a piece of type `pt` on `sq` could attack a square in `changed` either before
+/// or after a move, where `stable` is the set of squares occupied both before and after.
+fn reaches(pt: Piece, sq: Square, changed: Bitboard, stable: Bitboard) -> bool {
+    let rays = match pt {
+        Piece::Knight => return !(KNIGHT_MOVES[sq as usize] & changed).is_empty(),
+        Piece::Bishop => Bitboard::diags(sq),
+        Piece::Rook => Bitboard::hv(sq),
+        Piece::Queen => Bitboard::diags(sq) | Bitboard::hv(sq),
+        Piece::Pawn | Piece::King => Bitboard::EMPTY,
+    };
+    (rays & changed)
+        .into_iter()
+        .any(|target| (Bitboard::between(sq, target) & stable).is_empty())
+}
+
+/// Get the set of squares attacked by the pawns of `color`.
+fn pawn_attacks(b: &Board, color: Color) -> Bitboard {
+    let mut attacks = Bitboard::EMPTY;
+    for sq in b[Piece::Pawn] & b[color] {
+        attacks |= PAWN_ATTACKS[color as usize][sq as usize];
+    }
+    attacks
+}
+
+#[must_use]
+/// Get the mobility contribution of each piece type for each color, indexed first by piece type
+/// and then by color.
+/// Each entry is from the point of view of its own color, so the total mobility score is the sum
+/// of White's entries minus the sum of Black's.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, Color, Piece};
+/// use tomato::engine::evaluate::{mobility::evaluate_trace, Eval};
+///
+/// let b = Board::from_fen("4k3/8/8/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+/// let trace = evaluate_trace(&b);
+/// assert!(trace[Piece::Knight as usize][Color::White as usize].midgame() > Eval::DRAW);
+/// ```
+pub fn evaluate_trace(b: &Board) -> [[Score; 2]; Piece::NUM] {
+    trace(
+        b,
+        mobility_area(b, Color::White),
+        mobility_area(b, Color::Black),
+    )
+}
+
+/// Compute the mobility score of a position, where each side's pieces may only count targets in
+/// their own area.
+fn mobility(b: &Board, white_area: Bitboard, black_area: Bitboard) -> Score {
+    let trace = trace(b, white_area, black_area);
+    let mut score = Score::DRAW;
+    for pt_trace in trace {
+        score += pt_trace[Color::White as usize];
+        score -= pt_trace[Color::Black as usize];
+    }
+    score
+}
+
+/// Compute the mobility contribution of each piece type for each color, where each side's pieces
+/// may only count targets in their own area.
+fn trace(b: &Board, white_area: Bitboard, black_area: Bitboard) -> [[Score; 2]; Piece::NUM] {
+    let occupancy = b.occupancy();
+    let mut trace = [[Score::DRAW; 2]; Piece::NUM];
+    for (color, area) in [(Color::White, white_area), (Color::Black, black_area)] {
+        for pt in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+            for sq in b[pt] & b[color] {
+                trace[pt as usize][color as usize] +=
+                    for_piece(pt, piece_attacks(pt, color, sq, occupancy) & area);
+            }
+        }
+    }
+
+    trace
+}
+
+#[must_use]
+#[allow(clippy::cast_possible_wrap)]
+/// Get the mobility score for a single piece of type `pt` which can move to the squares in
+/// `targets`, from the point of view of the piece's color.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{movegen::KNIGHT_MOVES, Piece, Square};
+/// use tomato::engine::evaluate::{mobility::for_piece, Eval};
+///
+/// let corner = for_piece(Piece::Knight, KNIGHT_MOVES[Square::A1 as usize]);
+/// let center = for_piece(Piece::Knight, KNIGHT_MOVES[Square::E4 as usize]);
+/// assert!(corner.midgame() < center.midgame());
+/// ```
+pub fn for_piece(pt: Piece, targets: Bitboard) -> Score {
+    MOBILITY[pt as usize] * (targets.len() as i8 - MOBILITY_CENTER[pt as usize])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{game::Game, movegen::GenMode};
+
+    #[test]
+    /// Test that the mobility of a position and its color-mirror are exact opposites.
+    fn mirror_symmetric() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r1bq1b1r/ppp2kpp/2n5/3np3/2B5/8/PPPP1PPP/RNBQK2R w KQ - 0 7",
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+            "4k3/8/8/3q4/8/8/1R6/4K3 w - - 0 1",
+        ] {
+            let b = Board::from_fen(fen).unwrap();
+            assert_eq!(
+                evaluate(&b),
+                Score::DRAW - evaluate(&b.color_flip()),
+                "{fen}"
+            );
+        }
+    }
+
+    #[test]
+    /// Test that a queen's mobility is computed from its own attacks.
+    fn queen_mobility() {
+        // the black queen has 27 targets, and there is no black rook
+        let b = Board::from_fen("4k3/8/8/3q4/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            evaluate(&b),
+            Score::DRAW - MOBILITY[Piece::Queen as usize] * 15i8
+        );
+    }
+
+    #[test]
+    /// Test that a knight hemmed in by enemy pawns has lower safe mobility than one in open space,
+    /// even though their plain mobility is the same.
+    fn safe_cramped_knight() {
+        let open = Board::from_fen("4k3/8/8/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+        let cramped = Board::from_fen("4k3/4p3/1p5p/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(evaluate(&open), evaluate(&cramped));
+        assert_eq!(evaluate_safe(&open), evaluate(&open));
+        assert!(evaluate_safe(&cramped).midgame() < evaluate_safe(&open).midgame());
+    }
+
+    #[test]
+    /// Test that the mobility area excludes a side's own king and pawns, but not the enemy's.
+    fn area_excludes_king_and_pawns() {
+        let b = Board::from_fen("4k3/4p3/8/8/4N3/8/3P4/4K3 w - - 0 1").unwrap();
+        let area = mobility_area(&b, Color::White);
+        assert!(!area.contains(Square::E1));
+        assert!(!area.contains(Square::D2));
+        assert!(area.contains(Square::E4));
+        assert!(area.contains(Square::E7));
+        assert!(area.contains(Square::E8));
+    }
+
+    #[test]
+    /// Test that a friendly pawn does not count as a mobility target.
+    fn pawns_not_targets() {
+        let free = Board::from_fen("4k3/8/8/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+        let blocked = Board::from_fen("4k3/8/8/8/4N3/8/3P4/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            evaluate(&free) - evaluate(&blocked),
+            MOBILITY[Piece::Knight as usize]
+        );
+    }
+
+    #[test]
+    /// Test that the per-piece trace sums to the total mobility score.
+    fn trace_sums_to_evaluate() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+            "4k3/8/8/3q4/8/8/1R6/4K3 w - - 0 1",
+        ] {
+            let b = Board::from_fen(fen).unwrap();
+            let trace = evaluate_trace(&b);
+            let total = trace.iter().fold(Score::DRAW, |acc, pt_trace| {
+                acc + pt_trace[Color::White as usize] - pt_trace[Color::Black as usize]
+            });
+            assert_eq!(total, evaluate(&b), "{fen}");
+            assert_eq!(trace[Piece::Pawn as usize], [Score::DRAW; 2]);
+            assert_eq!(trace[Piece::King as usize], [Score::DRAW; 2]);
+        }
+    }
+
+    /// Check that adding the mobility delta of every legal move in the position given by `fen`
+    /// matches the mobility computed from scratch after making the move.
+    fn delta_helper(fen: &str) {
+        let mut g = Game::from_fen(fen).unwrap();
+        let orig_eval = evaluate(g.board());
+        for m in g.get_moves::<{ GenMode::All }>() {
+            let new_eval = match g.board().player {
+                Color::White => orig_eval + delta(g.board(), m),
+                Color::Black => orig_eval - delta(g.board(), m),
+            };
+            g.make_move(m);
+            assert_eq!(new_eval, evaluate(g.board()), "{fen} {m}");
+            g.undo().unwrap();
+        }
+    }
+
+    #[test]
+    /// Test that adding deltas matches the same result as taking the mobility from scratch.
+    fn mobility_delta_equals_base_result() {
+        delta_helper("r1bq1b1r/ppp2kpp/2n5/3np3/2B5/8/PPPP1PPP/RNBQK2R w KQ - 0 7");
+        delta_helper("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+        delta_helper("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1");
+    }
+
+    #[test]
+    /// Test mobility deltas for captures, promotions, and en passant.
+    fn delta_special_moves() {
+        delta_helper("r1bq1b1r/ppp2kpp/2n5/3n4/2BPp3/2P5/PP3PPP/RNBQK2R b KQ d3 0 8");
+        delta_helper("r4bkr/pPpq2pp/2n1b3/3n4/2BPp3/2P5/1P3PPP/RNBQK2R w KQ - 1 13");
+        delta_helper("rnbqkb1r/ppppp1pp/7n/4Pp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3");
+    }
+
+    #[test]
+    /// Test that the starting position has balanced mobility.
+    fn start_balanced() {
+        assert_eq!(evaluate(&Board::new()), Score::DRAW);
+    }
+}
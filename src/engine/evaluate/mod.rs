@@ -32,14 +32,37 @@
 //! is created, and then the final evaluation is a linear combination of those two.
 
 use std::{
-    fmt::{Display, Formatter},
+    fmt::{Debug, Display, Formatter},
     ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 
-use crate::base::{game::Game, Board, Color, Piece};
+use crate::base::{Board, Color, Piece};
 
+use self::{
+    cache::{EvalCache, PawnTable},
+    scored::ScoredGame,
+};
+
+pub mod bench;
+pub mod cache;
+pub mod center;
+pub mod development;
+pub mod files;
+pub mod king;
 pub mod material;
+pub mod mobility;
+pub mod outposts;
+pub mod passed;
+pub mod pawns;
 pub mod pst;
+pub mod rooks;
+pub mod scale;
+pub mod scored;
+pub mod see;
+pub mod threats;
+pub mod trace;
+pub mod trapped;
+pub mod tuning;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
 #[repr(C)]
@@ -66,16 +89,15 @@ pub mod pst;
 /// ```
 pub struct Eval(i16);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 /// A `Score` is a pair of two `Evals` - one for the midgame and one for the endgame.
 /// The values inside of a `Score` should never be mate values.
-pub struct Score {
-    /// The midgame-only evaluation of a position.
-    pub mg: Eval,
-    /// The endgame-only evaluation of a position.
-    pub eg: Eval,
-}
+///
+/// Both halves are packed into a single `i32` with the value `mg * 2^16 + eg`, so that adding,
+/// subtracting, or scaling a `Score` updates both halves with a single integer operation.
+/// The endgame half is stored as a signed number in the low 16 bits, so a negative endgame value
+/// borrows one from the midgame half; `midgame()` corrects for this when unpacking.
+pub struct Score(i32);
 
 /// The cutoff for pure midgame material.
 pub const MG_LIMIT: Eval = Eval::centipawns(2408);
@@ -83,20 +105,124 @@ pub const MG_LIMIT: Eval = Eval::centipawns(2408);
 /// The cutoff for pure endgame material.
 pub const EG_LIMIT: Eval = Eval::centipawns(1348);
 
+/// The most that pawns can add to a pawn-refined phase, reached when all 16 pawns are on the board.
+pub const PAWN_PHASE_WEIGHT: f32 = 0.25;
+
+/// The bonus for being the player to move.
+/// Having the move is worth more in the midgame, where there is more to do with it.
+pub const TEMPO: Score = Score::centipawns(14, 6);
+
+#[must_use]
+/// Heuristically evaluate a position from the point of view of the player to move.
+/// This sums every evaluation term into a single `Score`, scales and blends it by the game phase,
+/// and then puts it in perspective: a positive result is good for the player to move, and a
+/// negative one is good for their opponent.
+/// The player to move also gets the `TEMPO` bonus.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{evaluate, Eval, TEMPO};
+///
+/// assert_eq!(evaluate(&Board::new()), TEMPO.midgame());
+///
+/// // Black is missing a queen, which is bad for Black, the player to move
+/// let b = Board::from_fen("rnb1kbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+/// assert!(evaluate(&b) < Eval::DRAW);
+/// ```
+pub fn evaluate(b: &Board) -> Eval {
+    trace::trace_with(b, pst::evaluate(b), pawns::evaluate(b)).eval()
+}
+
+/// The margin by which the lazy estimate must fall outside of the search window for `lazy()` to
+/// return it.
+/// The lazy estimate only includes material and the piece-square tables, so this margin is an
+/// upper bound on how much every other term can swing the evaluation in a reasonable position.
+pub const LAZY_MARGIN: Eval = Eval::centipawns(400);
+
+#[must_use]
+/// Try to evaluate a position cheaply, from the point of view of the player to move.
+/// A quick estimate is made from material, the piece-square tables, and the tempo bonus alone.
+/// If that estimate is more than `LAZY_MARGIN` above `beta` or below `alpha`, the full evaluation
+/// would almost certainly fall outside the window too, so the estimate is returned.
+/// Otherwise, returns `None`, and the caller must run the full `evaluate()`.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{lazy, Eval};
+///
+/// // Black has no queen
+/// let b = Board::from_fen("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+/// let alpha = Eval::centipawns(-50);
+/// let beta = Eval::centipawns(50);
+/// assert!(lazy(&b, alpha, beta).unwrap() > beta);
+/// assert_eq!(lazy(&Board::new(), alpha, beta), None);
+/// ```
+pub fn lazy(b: &Board, alpha: Eval, beta: Eval) -> Option<Eval> {
+    let phase = phase_of(b);
+    let estimate = (material::evaluate(b) + pst::evaluate(b))
+        .blend(phase)
+        .in_perspective(b.player)
+        + TEMPO.blend(phase);
+
+    if estimate - LAZY_MARGIN >= beta || estimate + LAZY_MARGIN <= alpha {
+        Some(estimate)
+    } else {
+        None
+    }
+}
+
 #[must_use]
 #[allow(clippy::module_name_repetitions)]
-/// Heuristically evaluate a leaf position on a game.
-pub fn leaf_evaluate(g: &Game) -> Eval {
+/// Heuristically evaluate a leaf position on a game from White's point of view, using the PST
+/// score tracked incrementally by the game.
+/// The player to move gets the `TEMPO` bonus, so putting the result in the perspective of the
+/// player to move gives the same value as `evaluate()`.
+/// The pawn structure score is looked up in `pawn_table`, and only computed on a miss.
+pub fn leaf_evaluate(g: &ScoredGame, pawn_table: &mut PawnTable) -> Eval {
     let b = g.board();
-    let mg_npm = {
-        let mut total = Eval::DRAW;
-        for pt in Piece::NON_PAWNS {
-            total += material::value(pt).mg * b[pt].len();
-        }
-        total
-    };
-    let phase = calculate_phase(mg_npm);
-    (material::evaluate(b) + pst::evaluate(b)).blend(phase)
+    trace::trace_with(b, g.pst_score(), pawns::evaluate_cached(b, pawn_table)).blended_with_tempo()
+}
+
+#[must_use]
+/// Heuristically evaluate a position, using the cache to skip evaluation if this position has
+/// already been seen.
+/// The result is from White's point of view and, unlike `leaf_evaluate()`, does not include the
+/// tempo bonus.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{cache::EvalCache, evaluate_cached};
+///
+/// let b = Board::new();
+/// let mut cache = EvalCache::with_capacity(1024);
+/// let first = evaluate_cached(&b, &mut cache);
+/// assert_eq!(cache.probe(b.hash), Some(first));
+/// assert_eq!(evaluate_cached(&b, &mut cache), first);
+/// ```
+pub fn evaluate_cached(b: &Board, cache: &mut EvalCache) -> Eval {
+    if let Some(eval) = cache.probe(b.hash) {
+        return eval;
+    }
+    let eval = evaluate_board(b);
+    cache.store(b.hash, eval);
+    eval
+}
+
+/// Heuristically evaluate a position from scratch.
+fn evaluate_board(b: &Board) -> Eval {
+    trace::trace_with(b, pst::evaluate(b), pawns::evaluate(b)).blended()
+}
+
+/// Heuristically evaluate a position from scratch, using `tables` (the table for White followed by
+/// its mirror for Black) as the piece-square tables.
+fn evaluate_board_with(b: &Board, tables: &[pst::Pst; 2]) -> Eval {
+    trace::trace_with(b, pst::evaluate_with(b, tables), pawns::evaluate(b)).blended()
 }
 
 #[must_use]
@@ -116,7 +242,7 @@ pub fn phase_of(b: &Board) -> f32 {
     let mg_npm = {
         let mut total = Eval::DRAW;
         for pt in Piece::NON_PAWNS {
-            total += material::value(pt).mg * b[pt].len();
+            total += material::value(pt).midgame() * b[pt].len();
         }
         total
     };
@@ -133,6 +259,47 @@ pub fn calculate_phase(mg_npm: Eval) -> f32 {
     (EG_LIMIT - bounded_npm).float_val() / (EG_LIMIT - MG_LIMIT).float_val()
 }
 
+#[must_use]
+/// Get a blending float describing the current phase of the game, giving some midgame weight to
+/// pawns.
+/// Positions with many pawns but little other material (such as a K+8P vs K+8P ending) still play
+/// somewhat like a middlegame, so this phase will be slightly higher than `phase_of()` for them.
+/// Will range from 0 (full endgame) to 1 (full midgame).
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{pawn_phase_of, phase_of};
+///
+/// let b = Board::from_fen("4k3/pppppppp/8/8/8/8/PPPPPPPP/4K3 w - - 0 1").unwrap();
+/// assert!(phase_of(&b).eq(&0.0));
+/// assert!(pawn_phase_of(&b) > 0.0);
+/// ```
+pub fn pawn_phase_of(b: &Board) -> f32 {
+    let mg_npm = {
+        let mut total = Eval::DRAW;
+        for pt in Piece::NON_PAWNS {
+            total += material::value(pt).midgame() * b[pt].len();
+        }
+        total
+    };
+
+    calculate_pawn_phase(mg_npm, b[Piece::Pawn].len())
+}
+
+#[must_use]
+/// Get a blending float describing the current phase of the game, giving some midgame weight to
+/// pawns.
+/// Will range from 0 (full endgame) to 1 (full midgame).
+/// `mg_npm` is the amount of midgame non-pawn material on the board, and `num_pawns` is the total
+/// number of pawns of both colors.
+pub fn calculate_pawn_phase(mg_npm: Eval, num_pawns: u8) -> f32 {
+    let pawn_share = f32::from(num_pawns.min(16)) / 16.;
+
+    (calculate_phase(mg_npm) + PAWN_PHASE_WEIGHT * pawn_share).min(1.)
+}
+
 impl Eval {
     /// An evaluation which is smaller than every other "normal" evaluation.
     ///
@@ -193,6 +360,24 @@ impl Eval {
     /// The evaluation of a drawn position.
     pub const DRAW: Eval = Eval(0);
 
+    /// The highest evaluation of a position which is not a mate.
+    /// Everything from here up to `Eval::WHITE_MATE` is reserved for White's mates, and everything
+    /// above `Eval::WHITE_MATE` up to `Eval::MAX` is headroom for search bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::Eval;
+    ///
+    /// assert!(!Eval::NORMAL_MAX.is_mate());
+    /// assert!(Eval::NORMAL_MAX < Eval::mate_in(u8::MAX));
+    /// ```
+    pub const NORMAL_MAX: Eval = Eval(Eval::MATE_CUTOFF);
+
+    /// The lowest evaluation of a position which is not a mate.
+    /// This mirrors `Eval::NORMAL_MAX`, with the band below it reserved for Black's mates.
+    pub const NORMAL_MIN: Eval = Eval(-Eval::MATE_CUTOFF);
+
     /// The internal evaluation of a mate in 0 for White (i.e. White made the mating move on the
     /// previous ply).
     const MATE_0_VAL: i16 = 30_000;
@@ -227,6 +412,24 @@ impl Eval {
         Eval(Eval::MATE_0_VAL - (nplies as i16))
     }
 
+    #[must_use]
+    #[inline(always)]
+    /// Create an `Eval` based on the number of half-moves required for White to be mated.
+    /// This is the same as `-Eval::mate_in(nplies)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::Eval;
+    ///
+    /// assert_eq!(Eval::mated_in(0), Eval::BLACK_MATE);
+    /// assert!(Eval::mated_in(2) < Eval::mated_in(4));
+    /// assert!(Eval::mated_in(4).is_mate());
+    /// ```
+    pub const fn mated_in(nplies: u8) -> Eval {
+        Eval(-Eval::MATE_0_VAL + (nplies as i16))
+    }
+
     #[must_use]
     #[inline(always)]
     /// Step this evaluation back in time by `n` moves.
@@ -270,7 +473,7 @@ impl Eval {
     #[inline(always)]
     /// Is this evaluation a mate (i.e. a non-normal evaluation)?
     pub const fn is_mate(self) -> bool {
-        self.0 > Eval::MATE_CUTOFF || self.0 < -Eval::MATE_CUTOFF
+        self.0 > Eval::NORMAL_MAX.0 || self.0 < Eval::NORMAL_MIN.0
     }
 
     #[must_use]
@@ -296,6 +499,138 @@ impl Eval {
         })
     }
 
+    #[must_use]
+    /// Format this evaluation as the body of a UCI `score` field: `cp X` for a normal evaluation,
+    /// `mate N` if the side this evaluation favors mates in `N` moves, and `mate -N` if it is
+    /// mated in `N` moves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::Eval;
+    ///
+    /// assert_eq!(Eval::centipawns(150).uci_string(), "cp 150");
+    /// assert_eq!(Eval::mate_in(5).uci_string(), "mate 3");
+    /// assert_eq!(Eval::mated_in(4).uci_string(), "mate -2");
+    /// ```
+    pub fn uci_string(self) -> String {
+        match self.moves_to_mate() {
+            Some(n) if self > Eval::DRAW => format!("mate {n}"),
+            Some(n) => format!("mate -{n}"),
+            None => format!("cp {}", self.0),
+        }
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Get the larger of two evaluations.
+    /// Unlike `Ord::max`, this can be used in `const` contexts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::Eval;
+    ///
+    /// assert_eq!(Eval::DRAW.max(Eval::WHITE_MATE), Eval::WHITE_MATE);
+    /// ```
+    pub const fn max(self, other: Eval) -> Eval {
+        if self.0 < other.0 {
+            other
+        } else {
+            self
+        }
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Get the smaller of two evaluations.
+    /// Unlike `Ord::min`, this can be used in `const` contexts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::Eval;
+    ///
+    /// assert_eq!(Eval::DRAW.min(Eval::BLACK_MATE), Eval::BLACK_MATE);
+    /// ```
+    pub const fn min(self, other: Eval) -> Eval {
+        if other.0 < self.0 {
+            other
+        } else {
+            self
+        }
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Restrict this evaluation to the range `[lo, hi]`.
+    /// Requires that `lo <= hi`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::Eval;
+    ///
+    /// let lo = Eval::centipawns(-100);
+    /// let hi = Eval::centipawns(100);
+    /// assert_eq!(Eval::WHITE_MATE.clamp(lo, hi), hi);
+    /// assert_eq!(Eval::DRAW.clamp(lo, hi), Eval::DRAW);
+    /// ```
+    pub const fn clamp(self, lo: Eval, hi: Eval) -> Eval {
+        debug_assert!(lo.0 <= hi.0);
+        self.max(lo).min(hi)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Add two evaluations, clamping the result to the range `[Eval::MIN, Eval::MAX]` instead of
+    /// overflowing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::Eval;
+    ///
+    /// assert_eq!(Eval::MAX.saturating_add(Eval::centipawns(1)), Eval::MAX);
+    /// ```
+    pub const fn saturating_add(self, rhs: Eval) -> Eval {
+        Eval(self.0.saturating_add(rhs.0)).clamp(Eval::MIN, Eval::MAX)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Subtract two evaluations, clamping the result to the range `[Eval::MIN, Eval::MAX]` instead
+    /// of overflowing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::Eval;
+    ///
+    /// assert_eq!(Eval::MIN.saturating_sub(Eval::centipawns(1)), Eval::MIN);
+    /// ```
+    pub const fn saturating_sub(self, rhs: Eval) -> Eval {
+        Eval(self.0.saturating_sub(rhs.0)).clamp(Eval::MIN, Eval::MAX)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Add two non-mate evaluations, clamping the result to the range
+    /// `[Eval::NORMAL_MIN, Eval::NORMAL_MAX]`.
+    /// Unlike `saturating_add()`, a large sum can never be mistaken for a mate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::Eval;
+    ///
+    /// let high = Eval::centipawns(25_000);
+    /// assert_eq!(high.saturating_add_normal(high), Eval::NORMAL_MAX);
+    /// ```
+    pub const fn saturating_add_normal(self, rhs: Eval) -> Eval {
+        Eval(self.0.saturating_add(rhs.0)).clamp(Eval::NORMAL_MIN, Eval::NORMAL_MAX)
+    }
+
     #[inline(always)]
     #[must_use]
     /// Get the value in centipawns of this evaluation.
@@ -331,9 +666,10 @@ impl Score {
     pub const DRAW: Score = Score::centipawns(0, 0);
 
     #[must_use]
+    #[allow(clippy::cast_lossless)]
     /// Create a new `Score` by composing two evaluations together.
     pub const fn new(mg: Eval, eg: Eval) -> Score {
-        Score { mg, eg }
+        Score(((mg.0 as i32) << 16) + eg.0 as i32)
     }
 
     #[must_use]
@@ -342,6 +678,39 @@ impl Score {
         Score::new(Eval::centipawns(mg), Eval::centipawns(eg))
     }
 
+    #[inline(always)]
+    #[must_use]
+    /// Get the midgame half of this score.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::{Eval, Score};
+    ///
+    /// assert_eq!(Score::centipawns(10, -5).midgame(), Eval::centipawns(10));
+    /// ```
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn midgame(self) -> Eval {
+        // adding 2^15 undoes the borrow from a negative endgame half
+        Eval(((self.0 + 0x8000) >> 16) as i16)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Get the endgame half of this score.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::{Eval, Score};
+    ///
+    /// assert_eq!(Score::centipawns(10, -5).endgame(), Eval::centipawns(-5));
+    /// ```
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn endgame(self) -> Eval {
+        Eval(self.0 as i16)
+    }
+
     #[must_use]
     /// Blend the midgame and endgame
     pub fn blend(self, phase: f32) -> Eval {
@@ -349,7 +718,37 @@ impl Score {
         debug_assert!(0. <= phase);
         debug_assert!(phase <= 1.);
 
-        self.mg * phase + self.eg * (1. - phase)
+        self.midgame() * phase + self.endgame() * (1. - phase)
+    }
+
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    /// Blend the midgame and endgame using an integer phase, where `phase_max` is a full midgame
+    /// and 0 is a full endgame.
+    /// Unlike `blend`, this does no floating-point arithmetic, so the result is exact whenever the
+    /// interpolation divides evenly, which makes it convenient for tuning and testing.
+    ///
+    /// `phase` is clamped to lie between 0 and `phase_max`, and `phase_max` must be positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::evaluate::{Eval, Score};
+    ///
+    /// let s = Score::centipawns(100, 50);
+    /// assert_eq!(s.blend_phase(24, 24), Eval::centipawns(100));
+    /// assert_eq!(s.blend_phase(12, 24), Eval::centipawns(75));
+    /// ```
+    pub fn blend_phase(self, phase: i32, phase_max: i32) -> Eval {
+        debug_assert!(phase_max > 0);
+        let phase = i64::from(phase.clamp(0, phase_max));
+        let phase_max = i64::from(phase_max);
+        let mg = i64::from(self.midgame().0);
+        let eg = i64::from(self.endgame().0);
+
+        // each product is at most 2^15 * 2^31, so the sum cannot overflow an `i64`, and the result
+        // lies between `mg` and `eg`, so it always fits back into an `i16`
+        Eval(((mg * phase + eg * (phase_max - phase)) / phase_max) as i16)
     }
 }
 
@@ -378,7 +777,16 @@ impl Display for Eval {
 
 impl Display for Score {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.mg, self.eg)
+        write!(f, "({}, {})", self.midgame(), self.endgame())
+    }
+}
+
+impl Debug for Score {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Score")
+            .field("mg", &self.midgame())
+            .field("eg", &self.endgame())
+            .finish()
     }
 }
 
@@ -461,48 +869,52 @@ impl Neg for Eval {
 }
 
 impl AddAssign<Score> for Score {
+    #[inline(always)]
     fn add_assign(&mut self, rhs: Score) {
-        self.mg += rhs.mg;
-        self.eg += rhs.eg;
+        self.0 += rhs.0;
     }
 }
 
 impl SubAssign<Score> for Score {
+    #[inline(always)]
     fn sub_assign(&mut self, rhs: Score) {
-        self.mg -= rhs.mg;
-        self.eg -= rhs.eg;
+        self.0 -= rhs.0;
     }
 }
 
 impl Add<Score> for Score {
     type Output = Self;
 
+    #[inline(always)]
     fn add(self, rhs: Score) -> Self::Output {
-        Score::new(self.mg + rhs.mg, self.eg + rhs.eg)
+        Score(self.0 + rhs.0)
     }
 }
 
 impl Sub<Score> for Score {
     type Output = Self;
 
+    #[inline(always)]
     fn sub(self, rhs: Score) -> Self::Output {
-        Score::new(self.mg - rhs.mg, self.eg - rhs.eg)
+        Score(self.0 - rhs.0)
     }
 }
 
 impl Mul<i8> for Score {
     type Output = Self;
 
+    #[inline(always)]
     fn mul(self, rhs: i8) -> Self::Output {
-        Score::new(self.mg * rhs, self.eg * rhs)
+        Score(self.0 * i32::from(rhs))
     }
 }
 
 impl Mul<u8> for Score {
     type Output = Self;
 
+    #[inline(always)]
     fn mul(self, rhs: u8) -> Self::Output {
-        Score::new(self.mg * rhs, self.eg * rhs)
+        Score(self.0 * i32::from(rhs))
     }
 }
 
@@ -510,6 +922,59 @@ impl Mul<u8> for Score {
 mod tests {
 
     use super::*;
+    use crate::base::game::Game;
+
+    #[test]
+    /// Test that symmetric positions evaluate to exactly the tempo bonus for the player to move.
+    fn start_is_tempo() {
+        assert_eq!(evaluate_board(&Board::new()), Eval::DRAW);
+        assert_eq!(evaluate(&Board::new()), TEMPO.blend(1.));
+
+        // with no pieces besides pawns, the tempo is worth its endgame value
+        let b = Board::from_fen("4k3/pppppppp/8/8/8/8/PPPPPPPP/4K3 w - - 0 1").unwrap();
+        assert_eq!(evaluate(&b), TEMPO.endgame());
+    }
+
+    #[test]
+    /// Test that the lazy evaluation returns early when a queen up with a tight window, but
+    /// requires a full evaluation when the window straddles its estimate.
+    fn lazy_cutoff() {
+        let b =
+            Board::from_fen("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let estimate = lazy(&b, Eval::centipawns(-10), Eval::centipawns(10)).unwrap();
+        assert!(estimate > Eval::centipawns(10) + LAZY_MARGIN);
+
+        // from Black's point of view, the estimate is far below the window instead
+        let black =
+            Board::from_fen("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        assert!(lazy(&black, Eval::centipawns(-10), Eval::centipawns(10)).is_some());
+
+        let alpha = estimate - Eval::centipawns(10);
+        let beta = estimate + Eval::centipawns(10);
+        assert_eq!(lazy(&b, alpha, beta), None);
+    }
+
+    #[test]
+    /// Test that swapping the colors of a position negates its evaluation from White's point of
+    /// view, and so leaves it unchanged from the point of view of the player to move.
+    fn color_swap_negates() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r1bq1b1r/ppp2kpp/2n5/3np3/2B5/8/PPPP1PPP/RNBQK2R w KQ - 0 7",
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "2b1k3/5pp1/8/8/8/5PPP/8/4KB2 w - - 0 1",
+            "rnbqkb1r/ppppp1pp/7n/4Pp2/8/8/PPPP1PPP/RNBQKBNR w Kq f6 0 3",
+            "4k3/8/8/3q4/8/8/1R6/4K3 w - - 0 1",
+            "r1b1kb1r/2pp1ppp/1np1q3/p3P3/2P5/1P6/PB1NQPPP/R3KB1R b KQkq - 0 1",
+            "6k1/5ppp/8/8/1P6/P7/5PPP/6K1 b - - 0 1",
+        ] {
+            let b = Board::from_fen(fen).unwrap();
+            let flipped = b.color_flip();
+            assert_eq!(evaluate_board(&b), -evaluate_board(&flipped), "{fen}");
+            assert_eq!(evaluate(&b), evaluate(&flipped), "{fen}");
+        }
+    }
 
     #[test]
     #[allow(clippy::float_cmp)]
@@ -526,6 +991,240 @@ mod tests {
         assert_eq!(phase_of(&Board::default()), 1.0);
     }
 
+    #[test]
+    /// Test that a pawn-heavy ending without minor pieces is treated as more midgame-like than a
+    /// minor-piece ending without pawns.
+    fn pawn_phase_pawn_heavy() {
+        let minors = Board::from_fen("4k3/2n1b3/8/8/8/8/2N1B3/4K3 w - - 0 1").unwrap();
+        let pawns = Board::from_fen("4k3/pppppppp/8/8/8/8/PPPPPPPP/4K3 w - - 0 1").unwrap();
+        assert!(pawn_phase_of(&minors) < pawn_phase_of(&pawns));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    /// Test that the pawn-refined phase never exceeds full midgame.
+    fn pawn_phase_bounded() {
+        assert_eq!(pawn_phase_of(&Board::default()), 1.0);
+        assert_eq!(calculate_pawn_phase(Eval::DRAW, 0), 0.0);
+    }
+
+    #[test]
+    /// Test that the mate sentinels fit strictly inside the range `[Eval::MIN, Eval::MAX]`.
+    fn mates_inside_bounds() {
+        assert!(Eval::MIN < Eval::BLACK_MATE);
+        assert!(Eval::MIN < -Eval::mate_in(u8::MAX));
+        assert!(Eval::mate_in(u8::MAX) < Eval::MAX);
+        assert!(Eval::WHITE_MATE < Eval::MAX);
+    }
+
+    #[test]
+    /// Test that quicker mates are preferred, and that mate distances are decoded in moves.
+    fn mate_ordering() {
+        assert!(Eval::mate_in(1) > Eval::mate_in(3));
+        assert!(Eval::mated_in(1) < Eval::mated_in(3));
+        assert!(Eval::mated_in(u8::MAX) < Eval::DRAW);
+        assert_eq!(Eval::mated_in(5), -Eval::mate_in(5));
+        assert!(!Eval::centipawns(900).is_mate());
+
+        assert_eq!(Eval::mate_in(1).moves_to_mate(), Some(1));
+        assert_eq!(Eval::mate_in(3).moves_to_mate(), Some(2));
+        assert_eq!(Eval::mated_in(4).moves_to_mate(), Some(2));
+        assert_eq!(Eval::centipawns(900).moves_to_mate(), None);
+    }
+
+    #[test]
+    /// Test the UCI formatting of normal and mate evaluations.
+    fn uci_strings() {
+        assert_eq!(Eval::centipawns(150).uci_string(), "cp 150");
+        assert_eq!(Eval::centipawns(-37).uci_string(), "cp -37");
+        assert_eq!(Eval::DRAW.uci_string(), "cp 0");
+        // mate in 3 moves is 5 plies
+        assert_eq!(Eval::mate_in(5).uci_string(), "mate 3");
+        // mated in 2 moves is 4 plies
+        assert_eq!(Eval::mated_in(4).uci_string(), "mate -2");
+    }
+
+    #[test]
+    /// Test that saturating arithmetic at the edges of the evaluation range does not overflow.
+    fn saturating_bounds() {
+        let huge = Eval::centipawns(i16::MAX);
+        assert_eq!(Eval::MAX.saturating_add(Eval::centipawns(1)), Eval::MAX);
+        assert_eq!(Eval::MAX.saturating_add(huge), Eval::MAX);
+        assert_eq!(Eval::MIN.saturating_sub(Eval::centipawns(1)), Eval::MIN);
+        assert_eq!(Eval::MIN.saturating_sub(huge), Eval::MIN);
+        assert_eq!(Eval::MIN.saturating_add(-huge), Eval::MIN);
+        assert_eq!(
+            Eval::DRAW.saturating_add(Eval::centipawns(50)),
+            Eval::centipawns(50)
+        );
+    }
+
+    #[test]
+    /// Test that adding a huge delta to a high evaluation clamps instead of wrapping, and that
+    /// normal-only addition never reaches the mate band.
+    fn saturating_high_eval() {
+        let high = Eval::centipawns(28_500);
+        let huge = Eval::centipawns(20_000);
+        assert_eq!(high.saturating_add(huge), Eval::MAX);
+        assert_eq!((-high).saturating_sub(huge), Eval::MIN);
+
+        assert_eq!(high.saturating_add_normal(huge), Eval::NORMAL_MAX);
+        assert_eq!((-high).saturating_add_normal(-huge), Eval::NORMAL_MIN);
+        assert!(!high.saturating_add_normal(huge).is_mate());
+        assert_eq!(
+            high.saturating_add_normal(Eval::centipawns(100)),
+            Eval::centipawns(28_600)
+        );
+    }
+
+    #[test]
+    /// Test that `clamp` keeps values inside the window and leaves values in the window untouched.
+    fn clamp_window() {
+        let lo = Eval::centipawns(-30);
+        let hi = Eval::centipawns(30);
+        assert_eq!(Eval::MIN.clamp(lo, hi), lo);
+        assert_eq!(Eval::MAX.clamp(lo, hi), hi);
+        assert_eq!(Eval::centipawns(12).clamp(lo, hi), Eval::centipawns(12));
+        assert_eq!(lo.max(hi), hi);
+        assert_eq!(lo.min(hi), lo);
+    }
+
+    #[test]
+    /// Test that a cached evaluation is the same as a fresh one.
+    fn cached_matches_fresh() {
+        let mut cache = EvalCache::with_capacity(256);
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/5k2/6p1/8/5PPP/8/pb3P2/6K1 w - - 0 37",
+        ] {
+            let g = Game::from_fen(fen).unwrap();
+            let fresh = evaluate_board(g.board());
+            assert_eq!(evaluate_cached(g.board(), &mut cache), fresh);
+            // second lookup hits the cache
+            assert_eq!(cache.probe(g.board().hash), Some(fresh));
+            assert_eq!(evaluate_cached(g.board(), &mut cache), fresh);
+        }
+    }
+
+    #[test]
+    /// Test that positions sharing a cache slot do not get each other's evaluations.
+    fn cached_no_false_hits() {
+        // a single slot, so every position collides
+        let mut cache = EvalCache::with_capacity(1);
+        let g1 = Game::new();
+        let g2 = Game::from_fen("8/5k2/6p1/8/5PPP/8/pb3P2/6K1 w - - 0 37").unwrap();
+        assert_eq!(
+            evaluate_cached(g1.board(), &mut cache),
+            evaluate_board(g1.board())
+        );
+        assert_eq!(
+            evaluate_cached(g2.board(), &mut cache),
+            evaluate_board(g2.board())
+        );
+        assert_eq!(cache.probe(g1.board().hash), None);
+        assert_eq!(
+            evaluate_cached(g1.board(), &mut cache),
+            evaluate_board(g1.board())
+        );
+    }
+
+    #[test]
+    /// Test that a leaf evaluation using the pawn table matches a fresh evaluation, tempo bonus
+    /// included, both when the pawn structure is new and when it has been seen before.
+    fn leaf_pawn_table() {
+        let mut pawn_table = PawnTable::with_capacity(64);
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            // same pawns as above, different pieces
+            "r3k2r/p1ppqpb1/1n2pnp1/3P4/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1",
+            "8/5k2/6p1/8/5PPP/8/pb3P2/6K1 w - - 0 37",
+        ] {
+            let g = ScoredGame::from_fen(fen).unwrap();
+            assert_eq!(
+                leaf_evaluate(&g, &mut pawn_table).in_perspective(g.board().player),
+                evaluate(g.board())
+            );
+            assert_eq!(
+                pawn_table.probe(g.board().pawn_hash()),
+                Some(pawns::evaluate(g.board()))
+            );
+            assert_eq!(
+                leaf_evaluate(&g, &mut pawn_table).in_perspective(g.board().player),
+                evaluate(g.board())
+            );
+        }
+    }
+
+    #[test]
+    /// Test that `Eval::DRAW` is zero and is its own negation.
+    fn draw_is_zero() {
+        assert_eq!(Eval::DRAW.centipawn_val(), 0);
+        assert_eq!(-Eval::DRAW, Eval::DRAW);
+        assert_eq!(Eval::DRAW, Eval::centipawns(0));
+        assert_eq!(Score::DRAW, Score::new(Eval::DRAW, Eval::DRAW));
+    }
+
+    #[test]
+    /// Test that `Score::DRAW` is the additive identity and that every score cancels itself out.
+    fn score_identities() {
+        let scores = [
+            Score::DRAW,
+            Score::centipawns(1, -1),
+            Score::centipawns(-289, 0),
+            Score::centipawns(917, 816),
+            Score::centipawns(-5000, 5000),
+        ];
+        for s in scores {
+            assert_eq!(Score::DRAW + s, s);
+            assert_eq!(s + Score::DRAW, s);
+            assert_eq!(s - Score::DRAW, s);
+            assert_eq!(s - s, Score::DRAW);
+            assert_eq!(s * 1i8, s);
+
+            let mut acc = s;
+            acc += Score::DRAW;
+            assert_eq!(acc, s);
+            acc -= s;
+            assert_eq!(acc, Score::DRAW);
+        }
+        assert_eq!(Score::DRAW.blend(0.), Eval::DRAW);
+        assert_eq!(Score::DRAW.blend(0.5), Eval::DRAW);
+        assert_eq!(Score::DRAW.blend(1.), Eval::DRAW);
+    }
+
+    #[test]
+    /// Test that the midgame and endgame getters return exactly the halves a score was built from,
+    /// including negative values.
+    fn score_components() {
+        let s = Score::new(Eval(10), Eval(-5));
+        assert_eq!(s.midgame(), Eval(10));
+        assert_eq!(s.endgame(), Eval(-5));
+
+        let s = Score::new(Eval::MIN, Eval::MAX);
+        assert_eq!(s.midgame(), Eval::MIN);
+        assert_eq!(s.endgame(), Eval::MAX);
+    }
+
+    #[test]
+    /// Test that blending with an integer phase interpolates between the two halves of a score.
+    fn blend_integer_phase() {
+        let s = Score::centipawns(40, -20);
+        assert_eq!(s.blend_phase(256, 256), s.midgame());
+        assert_eq!(s.blend_phase(0, 256), s.endgame());
+        assert_eq!(s.blend_phase(128, 256), Eval::centipawns(10));
+        // out-of-range phases are clamped
+        assert_eq!(s.blend_phase(300, 256), s.midgame());
+        assert_eq!(s.blend_phase(-5, 256), s.endgame());
+        // the integer blend agrees with the floating-point one at the endpoints
+        assert_eq!(s.blend_phase(1, 1), s.blend(1.));
+        assert_eq!(s.blend_phase(0, 1), s.blend(0.));
+        // a huge phase range does not overflow
+        let big = Score::centipawns(i16::MAX, i16::MIN);
+        assert_eq!(big.blend_phase(i32::MAX, i32::MAX), big.midgame());
+        assert_eq!(big.blend_phase(0, i32::MAX), big.endgame());
+    }
+
     #[test]
     /// Test that multiplying scores doesn't screw up and cause weird overflows.
     fn score_multiply() {
@@ -537,4 +1236,50 @@ mod tests {
         assert_eq!(s1 * -2i8, Score::centipawns(578, 0));
         assert_eq!(s2 * -2i8, Score::centipawns(578, 400));
     }
+
+    #[test]
+    /// Test that packed score arithmetic matches doing the same arithmetic on each half
+    /// separately, including when a negative endgame half borrows from the midgame half.
+    fn packed_matches_naive() {
+        let rng = fastrand::Rng::with_seed(576);
+        let mut pairs = vec![
+            (0, 0),
+            (0, -1),
+            (-1, 0),
+            (-1, -1),
+            (1, -1),
+            (-1, 1),
+            (300, -300),
+        ];
+        for _ in 0..1000 {
+            pairs.push((rng.i16(-4000..4000), rng.i16(-4000..4000)));
+        }
+
+        for (i, &(mg1, eg1)) in pairs.iter().enumerate() {
+            let s1 = Score::centipawns(mg1, eg1);
+            assert_eq!(s1.midgame(), Eval(mg1));
+            assert_eq!(s1.endgame(), Eval(eg1));
+
+            let (mg2, eg2) = pairs[(i * 7 + 3) % pairs.len()];
+            let s2 = Score::centipawns(mg2, eg2);
+            assert_eq!(s1 + s2, Score::centipawns(mg1 + mg2, eg1 + eg2));
+            assert_eq!(s1 - s2, Score::centipawns(mg1 - mg2, eg1 - eg2));
+
+            let mut acc = s1;
+            acc += s2;
+            acc -= s1;
+            assert_eq!(acc, s2);
+
+            let k = rng.i8(-8..8);
+            assert_eq!(
+                s1 * k,
+                Score::centipawns(mg1 * i16::from(k), eg1 * i16::from(k))
+            );
+            let k = rng.u8(..8);
+            assert_eq!(
+                s1 * k,
+                Score::centipawns(mg1 * i16::from(k), eg1 * i16::from(k))
+            );
+        }
+    }
 }
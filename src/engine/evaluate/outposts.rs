@@ -0,0 +1,303 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Outposts for minor pieces.
+//!
+//! An outpost is a square in enemy territory which is defended by one of our pawns and which can
+//! never be attacked by an enemy pawn.
+//! A knight or bishop on an outpost can only be driven away by trading pieces for it.
+//! A minor piece one move away from an outpost gets a smaller bonus, since it can hop in later.
+//!
+//! A minor piece directly behind a pawn is also rewarded, since the pawn shields it from frontal
+//! attacks and blocks enemy rooks on its file.
+//! `structural()` computes both of these along with the bishop pair in a single pass, for use in
+//! the full evaluation.
+
+use crate::{
+    base::{
+        movegen::{KNIGHT_MOVES, PAWN_ATTACKS},
+        Bitboard, Board, Color, Piece, Square, MAGIC,
+    },
+    engine::evaluate::{material::BISHOP_PAIR, passed::front_span, Score},
+};
+
+/// The bonus for a knight standing on an outpost.
+pub const KNIGHT_OUTPOST: Score = Score::centipawns(30, 18);
+
+/// The bonus for a bishop standing on an outpost.
+pub const BISHOP_OUTPOST: Score = Score::centipawns(18, 6);
+
+/// The bonus for a knight which can move to an outpost.
+pub const REACHABLE_KNIGHT_OUTPOST: Score = Score::centipawns(14, 8);
+
+/// The bonus for a bishop which can move to an outpost.
+pub const REACHABLE_BISHOP_OUTPOST: Score = Score::centipawns(8, 3);
+
+/// The bonus for a knight or bishop directly behind a pawn of either color.
+pub const MINOR_BEHIND_PAWN: Score = Score::centipawns(9, 2);
+
+/// The ranks which count as enemy territory for outposts (ranks 4 through 6 from each side's point
+/// of view), indexed by color.
+const OUTPOST_RANKS: [Bitboard; 2] = [
+    Bitboard::new(0x0000_FFFF_FF00_0000),
+    Bitboard::new(0x0000_00FF_FFFF_0000),
+];
+
+#[must_use]
+/// Evaluate a position based on how well each side's minor pieces use outposts.
+pub fn evaluate(b: &Board) -> Score {
+    outposts_for(b, Color::White) - outposts_for(b, Color::Black)
+}
+
+#[must_use]
+/// Evaluate a position based on how many minor pieces each side has directly behind a pawn.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::outposts::{minor_behind_pawn, MINOR_BEHIND_PAWN};
+///
+/// // the knight on d3 is behind the pawn on d4
+/// let b = Board::from_fen("4k3/8/8/8/3P4/3N4/8/4K3 w - - 0 1").unwrap();
+/// assert_eq!(minor_behind_pawn(&b), MINOR_BEHIND_PAWN);
+/// ```
+pub fn minor_behind_pawn(b: &Board) -> Score {
+    behind_pawn_for(b, Color::White) - behind_pawn_for(b, Color::Black)
+}
+
+#[must_use]
+/// Evaluate the outposts, minor pieces behind pawns, and bishop pairs of a position in a single
+/// pass over each side's minor pieces.
+/// The result is always the same as the sum of `evaluate()`, `minor_behind_pawn()`, and
+/// `material::bishop_pair()`, but is cheaper to compute.
+pub fn structural(b: &Board) -> Score {
+    structural_for(b, Color::White) - structural_for(b, Color::Black)
+}
+
+#[must_use]
+/// Get the set of outpost squares for `color`.
+/// An outpost is a square in enemy territory, defended by a pawn of `color`, which no enemy pawn
+/// can ever attack.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, Color, Square};
+/// use tomato::engine::evaluate::outposts::outpost_squares;
+///
+/// let b = Board::from_fen("4k3/pp3ppp/8/8/2P1P3/8/PP3PPP/4K3 w - - 0 1").unwrap();
+/// assert!(outpost_squares(&b, Color::White).contains(Square::D5));
+/// ```
+pub fn outpost_squares(b: &Board, color: Color) -> Bitboard {
+    let own_pawns = b[Piece::Pawn] & b[color];
+    let enemy_pawns = b[Piece::Pawn] & b[!color];
+
+    let mut outposts = Bitboard::EMPTY;
+    for sq in OUTPOST_RANKS[color as usize] {
+        let defended = !(PAWN_ATTACKS[!color as usize][sq as usize] & own_pawns).is_empty();
+        if defended && (attack_span(sq, color) & enemy_pawns).is_empty() {
+            outposts.insert(sq);
+        }
+    }
+
+    outposts
+}
+
+/// Get the set of squares on the files adjacent to `sq` and in front of it from `color`'s point of
+/// view.
+/// An enemy pawn in this set could one day advance to attack `sq`.
+fn attack_span(sq: Square, color: Color) -> Bitboard {
+    let front = front_span(sq, color);
+    front.east() | front.west()
+}
+
+/// Get the outpost bonus for one color, from that color's point of view.
+fn outposts_for(b: &Board, color: Color) -> Score {
+    let outposts = outpost_squares(b, color);
+    let open_outposts = outposts & !b[color];
+    let occupancy = b.occupancy();
+    let mut score = Score::DRAW;
+
+    for sq in b[Piece::Knight] & b[color] {
+        score += knight_outpost(sq, outposts, open_outposts);
+    }
+    for sq in b[Piece::Bishop] & b[color] {
+        score += bishop_outpost(sq, outposts, open_outposts, occupancy);
+    }
+
+    score
+}
+
+/// Get the bonus for minor pieces behind pawns for one color, from that color's point of view.
+fn behind_pawn_for(b: &Board, color: Color) -> Score {
+    let minors = (b[Piece::Knight] | b[Piece::Bishop]) & b[color];
+
+    MINOR_BEHIND_PAWN * (minors & behind_pawns(b, color)).len()
+}
+
+/// Get the structural bonus (outposts, minors behind pawns, and the bishop pair) for one color,
+/// from that color's point of view.
+fn structural_for(b: &Board, color: Color) -> Score {
+    let outposts = outpost_squares(b, color);
+    let open_outposts = outposts & !b[color];
+    let occupancy = b.occupancy();
+    let shielded = behind_pawns(b, color);
+    let knights = b[Piece::Knight] & b[color];
+    let bishops = b[Piece::Bishop] & b[color];
+    let mut score = Score::DRAW;
+
+    for sq in knights {
+        score += knight_outpost(sq, outposts, open_outposts);
+        if shielded.contains(sq) {
+            score += MINOR_BEHIND_PAWN;
+        }
+    }
+    for sq in bishops {
+        score += bishop_outpost(sq, outposts, open_outposts, occupancy);
+        if shielded.contains(sq) {
+            score += MINOR_BEHIND_PAWN;
+        }
+    }
+    if bishops.len() >= 2 {
+        score += BISHOP_PAIR;
+    }
+
+    score
+}
+
+/// Get the squares directly behind a pawn of either color, from `color`'s point of view.
+fn behind_pawns(b: &Board, color: Color) -> Bitboard {
+    match color {
+        Color::White => b[Piece::Pawn].south(),
+        Color::Black => b[Piece::Pawn].north(),
+    }
+}
+
+/// Get the outpost bonus for a knight on `sq`.
+fn knight_outpost(sq: Square, outposts: Bitboard, open_outposts: Bitboard) -> Score {
+    if outposts.contains(sq) {
+        KNIGHT_OUTPOST
+    } else if (KNIGHT_MOVES[sq as usize] & open_outposts).is_empty() {
+        Score::DRAW
+    } else {
+        REACHABLE_KNIGHT_OUTPOST
+    }
+}
+
+/// Get the outpost bonus for a bishop on `sq`.
+fn bishop_outpost(
+    sq: Square,
+    outposts: Bitboard,
+    open_outposts: Bitboard,
+    occupancy: Bitboard,
+) -> Score {
+    if outposts.contains(sq) {
+        BISHOP_OUTPOST
+    } else if (MAGIC.bishop_attacks(occupancy, sq) & open_outposts).is_empty() {
+        Score::DRAW
+    } else {
+        REACHABLE_BISHOP_OUTPOST
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::evaluate::material::bishop_pair;
+
+    #[test]
+    /// Test that a knight on an outpost scores higher than one which can reach it, which in turn
+    /// scores higher than one with no outpost in sight.
+    fn graduated_knight_outpost() {
+        let on_outpost = Board::from_fen("4k3/pp3ppp/8/3N4/2P1P3/8/PP3PPP/4K3 w - - 0 1").unwrap();
+        let reaching = Board::from_fen("4k3/pp3ppp/8/8/2P1PN2/8/PP3PPP/4K3 w - - 0 1").unwrap();
+        let far = Board::from_fen("4k3/pp3ppp/8/8/2P1P3/8/PP3PPP/4K2N w - - 0 1").unwrap();
+
+        assert_eq!(evaluate(&on_outpost), KNIGHT_OUTPOST);
+        assert_eq!(evaluate(&reaching), REACHABLE_KNIGHT_OUTPOST);
+        assert_eq!(evaluate(&far), Score::DRAW);
+        for phase in [0., 0.5, 1.] {
+            assert!(evaluate(&on_outpost).blend(phase) > evaluate(&reaching).blend(phase));
+            assert!(evaluate(&reaching).blend(phase) > evaluate(&far).blend(phase));
+        }
+    }
+
+    #[test]
+    /// Test that a knight on e5 backed by a pawn scores higher than the same knight with no pawn
+    /// defending it.
+    fn protected_knight_e5() {
+        let protected = Board::from_fen("4k3/pp4pp/8/4N3/3P4/8/PP3PPP/4K3 w - - 0 1").unwrap();
+        let unprotected = Board::from_fen("4k3/pp4pp/8/4N3/8/8/PP3PPP/4K3 w - - 0 1").unwrap();
+        assert!(outpost_squares(&protected, Color::White).contains(Square::E5));
+        assert!(!outpost_squares(&unprotected, Color::White).contains(Square::E5));
+        assert_eq!(evaluate(&protected), KNIGHT_OUTPOST);
+        assert_eq!(evaluate(&unprotected), Score::DRAW);
+    }
+
+    #[test]
+    /// Test that a square which an enemy pawn can still attack is not an outpost.
+    fn pawn_can_attack() {
+        // the pawn on e7 can push to e6 and hit d5
+        let b = Board::from_fen("4k3/pp2pppp/8/3N4/2P1P3/8/PP3PPP/4K3 w - - 0 1").unwrap();
+        assert!(!outpost_squares(&b, Color::White).contains(Square::D5));
+        assert_eq!(evaluate(&b), Score::DRAW);
+    }
+
+    #[test]
+    /// Test that outposts work for Black as well.
+    fn black_outpost() {
+        let b = Board::from_fen("4k3/pp3ppp/8/2p1p3/3n4/8/PP3PPP/4K3 w - - 0 1").unwrap();
+        assert!(outpost_squares(&b, Color::Black).contains(Square::D4));
+        assert_eq!(evaluate(&b), Score::DRAW - KNIGHT_OUTPOST);
+    }
+
+    #[test]
+    /// Test that a minor piece behind a pawn of either color gets the bonus, but one beside a
+    /// pawn does not.
+    fn behind_pawn() {
+        let own = Board::from_fen("4k3/8/8/8/3P4/3N4/8/4K3 w - - 0 1").unwrap();
+        let enemy = Board::from_fen("4k3/8/8/3p4/3B4/8/8/4K3 w - - 0 1").unwrap();
+        let beside = Board::from_fen("4k3/8/8/8/3PN3/8/8/4K3 w - - 0 1").unwrap();
+        let black = Board::from_fen("4k3/8/3n4/3P4/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(minor_behind_pawn(&own), MINOR_BEHIND_PAWN);
+        assert_eq!(minor_behind_pawn(&enemy), MINOR_BEHIND_PAWN);
+        assert_eq!(minor_behind_pawn(&beside), Score::DRAW);
+        assert_eq!(minor_behind_pawn(&black), Score::DRAW - MINOR_BEHIND_PAWN);
+    }
+
+    #[test]
+    /// Test that the combined structural pass equals the sum of the terms it replaces.
+    fn structural_matches_terms() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "4k3/pp3ppp/8/3N4/2P1P3/8/PP3PPP/4K3 w - - 0 1",
+            "4k3/pp3ppp/8/2p1p3/3n4/8/PP3PPP/4K3 w - - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r1bq1rk1/pp2bppp/2n1pn2/2pp4/3P4/2PBPN2/PP1N1PPP/R1BQ1RK1 w - - 0 8",
+            "2b1k3/5pp1/8/8/8/5PPP/8/4KB2 w - - 0 1",
+        ] {
+            let b = Board::from_fen(fen).unwrap();
+            assert_eq!(
+                structural(&b),
+                evaluate(&b) + minor_behind_pawn(&b) + bishop_pair(&b),
+                "{fen}"
+            );
+        }
+    }
+}
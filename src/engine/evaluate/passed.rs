@@ -0,0 +1,279 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Passed pawns.
+//!
+//! A passed pawn is a pawn with no enemy pawns in front of it on its own file or on either adjacent
+//! file, so no pawn can ever stop it from promoting.
+//! Passed pawns grow more valuable as they advance, especially in the endgame.
+//! In a pure pawn ending, a passed pawn which the enemy king cannot catch and nothing can blockade
+//! is decisive, so it gets a bonus worth nearly as much as the queen it will become.
+
+use crate::{
+    base::{Bitboard, Board, Color, Piece, Square},
+    engine::evaluate::Score,
+};
+
+/// The bonus for each passed pawn, indexed by its rank relative to its own side.
+pub const PASSED_PAWN: [Score; 8] = [
+    Score::centipawns(0, 0),
+    Score::centipawns(4, 8),
+    Score::centipawns(6, 12),
+    Score::centipawns(10, 22),
+    Score::centipawns(20, 40),
+    Score::centipawns(35, 70),
+    Score::centipawns(60, 110),
+    Score::centipawns(0, 0),
+];
+
+/// The bonus for having a passed pawn which the enemy king cannot catch in a pawn ending.
+/// This is nearly the value of a queen, less the pawn that becomes it.
+pub const UNSTOPPABLE_PASSER: Score = Score::centipawns(0, 700);
+
+#[must_use]
+/// Evaluate a position based on its passed pawns.
+pub fn evaluate(b: &Board) -> Score {
+    passers(b, Color::White) - passers(b, Color::Black) + unstoppable(b, Color::White)
+        - unstoppable(b, Color::Black)
+}
+
+#[must_use]
+/// Get the set of squares in front of a pawn on `sq` of the given color, on its own file.
+/// These are the squares the pawn must pass through to promote.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Bitboard, Color, Square};
+/// use tomato::engine::evaluate::passed::front_span;
+///
+/// let span = front_span(Square::E6, Color::White);
+/// assert_eq!(span, Bitboard::from_iter([Square::E7, Square::E8]));
+/// ```
+pub fn front_span(sq: Square, color: Color) -> Bitboard {
+    let pawn = Bitboard::from(sq);
+    match color {
+        Color::White => pawn.north().north_fill(),
+        Color::Black => pawn.south().south_fill(),
+    }
+}
+
+#[must_use]
+/// Get the set of squares in front of a pawn on `sq` of the given color, on its own file and the
+/// two adjacent files.
+/// If no enemy pawns are in this span, then the pawn is passed.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Color, Square};
+/// use tomato::engine::evaluate::passed::passed_span;
+///
+/// let span = passed_span(Square::E4, Color::White);
+/// assert!(span.contains(Square::D5));
+/// assert!(span.contains(Square::F8));
+/// assert!(!span.contains(Square::E4));
+/// assert!(!span.contains(Square::C5));
+/// ```
+pub fn passed_span(sq: Square, color: Color) -> Bitboard {
+    let front = front_span(sq, color);
+    front | front.east() | front.west()
+}
+
+#[must_use]
+/// Determine whether the pawn of color `pawn_color` on `pawn_sq` is passed.
+pub fn is_passed(b: &Board, pawn_sq: Square, pawn_color: Color) -> bool {
+    (passed_span(pawn_sq, pawn_color) & b[Piece::Pawn] & b[!pawn_color]).is_empty()
+}
+
+#[must_use]
+#[allow(clippy::missing_panics_doc)]
+/// Determine whether the enemy king can catch the pawn of color `pawn_color` on `pawn_sq` before it
+/// promotes, using the rule of the square.
+/// The king catches the pawn if it can reach the promotion square no later than the pawn does,
+/// accounting for which side is to move and for the pawn's double push from its starting rank.
+///
+/// This only considers the king and the pawn, so it is only reliable in pawn endings where nothing
+/// else blocks the pawn's path.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, Color, Square};
+/// use tomato::engine::evaluate::passed::can_king_catch_pawn;
+///
+/// let b = Board::from_fen("4k3/8/8/P7/8/8/8/4K3 b - - 0 1").unwrap();
+/// assert!(can_king_catch_pawn(&b, Square::A5, Color::White));
+/// ```
+pub fn can_king_catch_pawn(board: &Board, pawn_sq: Square, pawn_color: Color) -> bool {
+    let (promote_rank, plies_to_promote) = match pawn_color {
+        Color::White => (7, 7 - pawn_sq.rank()),
+        Color::Black => (0, pawn_sq.rank()),
+    };
+    // a pawn on its starting rank can double push
+    let pawn_dist = plies_to_promote.min(5);
+    let promote_sq = Square::new(promote_rank, pawn_sq.file()).unwrap();
+    let king_dist = board.king_sqs[!pawn_color as usize].chebyshev_to(promote_sq);
+    // the defender gets a tempo if it is their move
+    let tempo = u8::from(board.player != pawn_color);
+
+    king_dist <= pawn_dist + tempo
+}
+
+/// Get the rank-based bonus for every passed pawn of one color, from that color's point of view.
+fn passers(b: &Board, color: Color) -> Score {
+    let mut score = Score::DRAW;
+    for sq in b[Piece::Pawn] & b[color] {
+        if is_passed(b, sq, color) {
+            let rank = match color {
+                Color::White => sq.rank(),
+                Color::Black => 7 - sq.rank(),
+            };
+            score += PASSED_PAWN[rank as usize];
+        }
+    }
+
+    score
+}
+
+/// Get the bonus for having an unstoppable passed pawn for one color, from that color's point of
+/// view.
+fn unstoppable(b: &Board, color: Color) -> Score {
+    // only in pawn endings is the rule of the square reliable
+    let enemy_pieces = b[!color] & !(b[Piece::Pawn] | b[Piece::King]);
+    if !enemy_pieces.is_empty() {
+        return Score::DRAW;
+    }
+
+    for sq in b[Piece::Pawn] & b[color] {
+        // friendly pieces can step out of the pawn's way, but the enemy king blockades it, and so
+        // does our own king, since it cannot leave without walking into the pawn's path
+        let blockers = b[!color] | (b[Piece::King] & b[color]);
+        if is_passed(b, sq, color)
+            && (front_span(sq, color) & blockers).is_empty()
+            && !can_king_catch_pawn(b, sq, color)
+        {
+            return UNSTOPPABLE_PASSER;
+        }
+    }
+
+    Score::DRAW
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::evaluate::material;
+
+    /// Helper function to check whether the king can catch the pawn on `pawn_sq` in the position
+    /// given by `fen`.
+    fn catch_helper(fen: &str, pawn_sq: Square, expected: bool) {
+        let b = Board::from_fen(fen).unwrap();
+        let pawn_color = b.color_at_square(pawn_sq).unwrap();
+        assert_eq!(can_king_catch_pawn(&b, pawn_sq, pawn_color), expected);
+    }
+
+    #[test]
+    /// Test that a king inside the square catches the pawn when it is the defender's move.
+    fn inside_square_with_move() {
+        catch_helper("4k3/8/8/P7/8/8/8/4K3 b - - 0 1", Square::A5, true);
+    }
+
+    #[test]
+    /// Test that a king on the edge of the square cannot catch the pawn if the pawn moves first.
+    fn inside_square_without_move() {
+        catch_helper("4k3/8/8/P7/8/8/8/4K3 w - - 0 1", Square::A5, false);
+    }
+
+    #[test]
+    /// Test that a king outside the square cannot catch the pawn, even with the move.
+    fn outside_square() {
+        catch_helper("5k2/8/8/P7/8/8/8/4K3 b - - 0 1", Square::A5, false);
+        catch_helper("5k2/8/8/P7/8/8/8/4K3 w - - 0 1", Square::A5, false);
+    }
+
+    #[test]
+    /// Test that the rule of the square works for Black's pawns too.
+    fn black_pawn_race() {
+        catch_helper("4k3/8/8/8/p7/8/8/4K3 w - - 0 1", Square::A4, true);
+        catch_helper("4k3/8/8/8/p7/8/8/4K3 b - - 0 1", Square::A4, false);
+    }
+
+    #[test]
+    /// Test that a pawn on its starting rank is treated as one step closer due to the double push.
+    fn double_push_race() {
+        // the king is six steps from a8, but the pawn needs only five moves thanks to the double
+        // push
+        catch_helper("6k1/8/8/8/8/8/P7/7K w - - 0 1", Square::A2, false);
+        catch_helper("6k1/8/8/8/8/8/P7/7K b - - 0 1", Square::A2, true);
+    }
+
+    #[test]
+    /// Test that an uncatchable passer in a pawn ending is worth nearly a queen, and a catchable
+    /// one only gets the normal passed pawn bonus.
+    fn unstoppable_bonus() {
+        let runs = Board::from_fen("5k2/8/8/P7/8/8/8/4K3 b - - 0 1").unwrap();
+        let caught = Board::from_fen("4k3/8/8/P7/8/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(evaluate(&runs), PASSED_PAWN[4] + UNSTOPPABLE_PASSER);
+        assert!(
+            evaluate(&runs).endgame() + material::value(Piece::Pawn).endgame()
+                >= material::value(Piece::Queen).endgame() * 0.9
+        );
+        assert_eq!(evaluate(&caught), PASSED_PAWN[4]);
+    }
+
+    #[test]
+    /// Test that only the normal passed pawn bonus is given when the defender still has pieces to
+    /// stop the pawn.
+    fn pieces_can_stop() {
+        let b = Board::from_fen("5k2/8/8/P7/8/8/7r/4K3 b - - 0 1").unwrap();
+        assert_eq!(evaluate(&b), PASSED_PAWN[4]);
+    }
+
+    #[test]
+    /// Test that a passer whose path is blocked by its own king is not considered unstoppable,
+    /// even if the enemy king is far away.
+    fn blockaded_passer() {
+        let b = Board::from_fen("K7/8/P7/8/8/8/8/7k b - - 0 1").unwrap();
+        assert_eq!(evaluate(&b), PASSED_PAWN[5]);
+    }
+
+    #[test]
+    /// Test that a friendly piece in front of a passer does not stop it from being unstoppable,
+    /// since the piece can simply move out of the way.
+    fn friendly_piece_in_path() {
+        let b = Board::from_fen("7k/N7/P7/8/8/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(evaluate(&b), PASSED_PAWN[5] + UNSTOPPABLE_PASSER);
+    }
+
+    #[test]
+    /// Test that Black's passed pawns get the same bonus as White's, mirrored.
+    fn black_passer_bonus() {
+        let b = Board::from_fen("4k3/8/8/8/8/p7/2K5/8 w - - 0 1").unwrap();
+        assert_eq!(evaluate(&b), Score::DRAW - PASSED_PAWN[5]);
+    }
+
+    #[test]
+    /// Test that pawns facing enemy pawns on adjacent files are not passed.
+    fn not_passed() {
+        let b = Board::from_fen("4k3/1p6/8/P7/8/8/7P/4K3 w - - 0 1").unwrap();
+        assert!(!is_passed(&b, Square::A5, Color::White));
+        assert!(!is_passed(&b, Square::B7, Color::Black));
+        assert!(is_passed(&b, Square::H2, Color::White));
+    }
+}
@@ -0,0 +1,341 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Pawn structure.
+//!
+//! Rules in this module depend only on the placement of pawns.
+
+use crate::{
+    base::{movegen::PAWN_ATTACKS, Bitboard, Board, Color, Piece, Square},
+    engine::evaluate::{cache::PawnTable, Score},
+};
+
+/// The bonus for having a healthy pawn majority on one wing.
+pub const WING_MAJORITY: Score = Score::centipawns(0, 18);
+
+/// The bonus for a connected pawn, which is either defended by a friendly pawn or standing next to
+/// one, indexed by the pawn's rank from its own side's point of view.
+/// Connected pawns further up the board are worth more, since they are harder to stop.
+pub const CONNECTED: [Score; 8] = [
+    Score::centipawns(0, 0),
+    Score::centipawns(3, 0),
+    Score::centipawns(5, 2),
+    Score::centipawns(7, 5),
+    Score::centipawns(13, 11),
+    Score::centipawns(24, 22),
+    Score::centipawns(40, 45),
+    Score::centipawns(0, 0),
+];
+
+/// The penalty for a backward pawn on a file with enemy pawns.
+pub const BACKWARD: Score = Score::centipawns(-8, -10);
+
+/// The penalty for a backward pawn on a file with no enemy pawns, where it can be attacked by
+/// enemy rooks.
+pub const BACKWARD_SEMI_OPEN: Score = Score::centipawns(-16, -14);
+
+#[must_use]
+/// Evaluate a position based on its pawn structure.
+pub fn evaluate(b: &Board) -> Score {
+    pawn_majority(b) + backward(b) + connected(b)
+}
+
+#[must_use]
+/// Evaluate a position based on its pawn structure, using `table` to avoid recomputing the
+/// evaluation of pawn structures which have been seen before.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{cache::PawnTable, pawns};
+///
+/// let b = Board::from_fen("4k3/pp3ppp/8/8/8/8/PPP2PPP/4K3 w - - 0 1").unwrap();
+/// let mut table = PawnTable::with_capacity(1024);
+/// assert_eq!(pawns::evaluate_cached(&b, &mut table), pawns::evaluate(&b));
+/// assert_eq!(table.probe(b.pawn_hash()), Some(pawns::evaluate(&b)));
+/// ```
+pub fn evaluate_cached(b: &Board, table: &mut PawnTable) -> Score {
+    let key = b.pawn_hash();
+    if let Some(score) = table.probe(key) {
+        return score;
+    }
+
+    let score = evaluate(b);
+    table.store(key, score);
+    score
+}
+
+#[must_use]
+/// Evaluate the pawn majorities on each wing.
+/// A side with more pawns than its opponent on the queenside (files A through C) or the kingside
+/// (files F through H) can eventually create a passed pawn there, which is mostly relevant in the
+/// endgame.
+/// A majority only counts if it is healthy, meaning that none of its pawns are doubled.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{pawns::pawn_majority, Eval};
+///
+/// let b = Board::from_fen("4k3/pp3ppp/8/8/8/8/PPP2PPP/4K3 w - - 0 1").unwrap();
+/// assert!(pawn_majority(&b).endgame() > Eval::DRAW);
+/// ```
+pub fn pawn_majority(board: &Board) -> Score {
+    let queenside = Bitboard::FILES[0] | Bitboard::FILES[1] | Bitboard::FILES[2];
+    let kingside = Bitboard::FILES[5] | Bitboard::FILES[6] | Bitboard::FILES[7];
+
+    let mut score = Score::DRAW;
+    for wing in [queenside, kingside] {
+        let white_pawns = board[Piece::Pawn] & board[Color::White] & wing;
+        let black_pawns = board[Piece::Pawn] & board[Color::Black] & wing;
+        if white_pawns.len() > black_pawns.len() && is_healthy(white_pawns) {
+            score += WING_MAJORITY;
+        } else if black_pawns.len() > white_pawns.len() && is_healthy(black_pawns) {
+            score -= WING_MAJORITY;
+        }
+    }
+
+    score
+}
+
+#[must_use]
+/// Get the backward pawns of `color`.
+/// A pawn is backward if no friendly pawn on an adjacent file is level with or behind it, so it
+/// cannot be defended by another pawn, and the square in front of it is attacked by an enemy pawn,
+/// so it cannot safely advance to find support.
+/// Pawns with no friendly pawns on either adjacent file are isolated rather than backward, so they
+/// are excluded.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Bitboard, Board, Color, Square};
+/// use tomato::engine::evaluate::pawns::backward_pawns;
+///
+/// // the pawn on d3 has been left behind by the pawn on c4, and e5 stops it from advancing
+/// let b = Board::from_fen("4k3/8/5p2/4p3/2P5/3P4/8/4K3 w - - 0 1").unwrap();
+/// assert_eq!(backward_pawns(&b, Color::White), Bitboard::from(Square::D3));
+/// ```
+pub fn backward_pawns(board: &Board, color: Color) -> Bitboard {
+    let pawns = board[Piece::Pawn] & board[color];
+    let enemy_pawns = board[Piece::Pawn] & board[!color];
+    let mut enemy_pawn_attacks = Bitboard::EMPTY;
+    for sq in enemy_pawns {
+        enemy_pawn_attacks |= PAWN_ATTACKS[!color as usize][sq as usize];
+    }
+
+    let mut backward = Bitboard::EMPTY;
+    for sq in pawns {
+        let rank = Bitboard::RANKS[sq.rank() as usize];
+        let (level_or_behind, stop_sq) = match color {
+            Color::White => (rank.south_fill(), sq.try_offset(0, 1)),
+            Color::Black => (rank.north_fill(), sq.try_offset(0, -1)),
+        };
+        let Some(stop_sq) = stop_sq else {
+            continue;
+        };
+        let neighbors = pawns & adjacent_files(sq);
+        let supporters = neighbors & level_or_behind;
+        if !neighbors.is_empty() && supporters.is_empty() && enemy_pawn_attacks.contains(stop_sq) {
+            backward.insert(sq);
+        }
+    }
+
+    backward
+}
+
+/// Evaluate the backward pawns of each side.
+fn backward(board: &Board) -> Score {
+    let penalty = |color: Color| {
+        let enemy_pawns = board[Piece::Pawn] & board[!color];
+        let mut score = Score::DRAW;
+        for sq in backward_pawns(board, color) {
+            score += if (enemy_pawns & Bitboard::FILES[sq.file() as usize]).is_empty() {
+                BACKWARD_SEMI_OPEN
+            } else {
+                BACKWARD
+            };
+        }
+        score
+    };
+
+    penalty(Color::White) - penalty(Color::Black)
+}
+
+#[must_use]
+/// Get the connected pawns of `color`: those which are defended by a friendly pawn, or which
+/// stand directly beside one as part of a phalanx.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Bitboard, Board, Color, Square};
+/// use tomato::engine::evaluate::pawns::connected_pawns;
+///
+/// // d4 and e4 form a phalanx, and c3 defends d4, but c3 itself is unsupported
+/// let b = Board::from_fen("4k3/8/8/8/3PP3/2P5/8/4K3 w - - 0 1").unwrap();
+/// assert_eq!(
+///     connected_pawns(&b, Color::White),
+///     Bitboard::from_squares(&[Square::D4, Square::E4])
+/// );
+/// ```
+pub fn connected_pawns(board: &Board, color: Color) -> Bitboard {
+    let pawns = board[Piece::Pawn] & board[color];
+    let mut connected = Bitboard::EMPTY;
+    for sq in pawns {
+        let sq_bb = Bitboard::from(sq);
+        // a pawn's defenders are on the squares an enemy pawn there would attack
+        let defended = !(PAWN_ATTACKS[!color as usize][sq as usize] & pawns).is_empty();
+        let phalanx = !((sq_bb.east() | sq_bb.west()) & pawns).is_empty();
+        if defended || phalanx {
+            connected.insert(sq);
+        }
+    }
+
+    connected
+}
+
+/// Evaluate the connected pawns of each side.
+fn connected(board: &Board) -> Score {
+    let mut score = Score::DRAW;
+    for sq in connected_pawns(board, Color::White) {
+        score += CONNECTED[sq.rank() as usize];
+    }
+    for sq in connected_pawns(board, Color::Black) {
+        score -= CONNECTED[sq.opposite().rank() as usize];
+    }
+
+    score
+}
+
+/// Get the files adjacent to the file of `sq`, not including its own file.
+fn adjacent_files(sq: Square) -> Bitboard {
+    let file = Bitboard::FILES[sq.file() as usize];
+    file.east() | file.west()
+}
+
+/// Determine whether a set of pawns has no doubled pawns.
+fn is_healthy(pawns: Bitboard) -> bool {
+    Bitboard::FILES
+        .iter()
+        .all(|&file| !(pawns & file).more_than_one())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::evaluate::Eval;
+
+    #[test]
+    /// Test that a 3-vs-2 queenside majority is an endgame advantage but worth nothing in the
+    /// midgame.
+    fn queenside_majority() {
+        let b = Board::from_fen("4k3/pp3ppp/8/8/8/8/PPP2PPP/4K3 w - - 0 1").unwrap();
+        let score = pawn_majority(&b);
+        assert_eq!(score, WING_MAJORITY);
+        assert!(score.blend(0.) > Eval::DRAW);
+        assert_eq!(score.blend(1.), Eval::DRAW);
+    }
+
+    #[test]
+    /// Test that a balanced pawn structure has no majority.
+    fn balanced() {
+        assert_eq!(pawn_majority(&Board::new()), Score::DRAW);
+        let b = Board::from_fen("4k3/pp3ppp/8/8/8/8/PP3PPP/4K3 w - - 0 1").unwrap();
+        assert_eq!(pawn_majority(&b), Score::DRAW);
+    }
+
+    #[test]
+    /// Test that a majority made of doubled pawns is not rewarded.
+    fn doubled_majority() {
+        let b = Board::from_fen("4k3/pp3ppp/8/8/8/1P6/PP3PPP/4K3 w - - 0 1").unwrap();
+        assert_eq!(pawn_majority(&b), Score::DRAW);
+    }
+
+    #[test]
+    /// Test that a textbook backward pawn is penalized, and that a pawn level with a neighbor is
+    /// not.
+    fn backward_vs_supported() {
+        let backward_b = Board::from_fen("4k3/8/5p2/4p3/2P5/3P4/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            backward_pawns(&backward_b, Color::White),
+            Bitboard::from(Square::D3)
+        );
+        assert!(backward_pawns(&backward_b, Color::Black).is_empty());
+        // there are no Black pawns on the d-file
+        assert_eq!(backward(&backward_b), BACKWARD_SEMI_OPEN);
+
+        // the pawn on e3 keeps d3 company
+        let supported = Board::from_fen("4k3/8/5p2/4p3/2P5/3PP3/8/4K3 w - - 0 1").unwrap();
+        assert!(backward_pawns(&supported, Color::White).is_empty());
+        assert!(backward_pawns(&supported, Color::Black).is_empty());
+        assert_eq!(backward(&supported), Score::DRAW);
+    }
+
+    #[test]
+    /// Test that a backward pawn facing an enemy pawn on its file gets the smaller penalty.
+    fn backward_closed_file() {
+        let b = Board::from_fen("4k3/3p4/8/4p3/2P5/3P4/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(backward_pawns(&b, Color::White), Bitboard::from(Square::D3));
+        assert!(backward_pawns(&b, Color::Black).is_empty());
+        assert_eq!(backward(&b), BACKWARD);
+    }
+
+    #[test]
+    /// Test that an isolated pawn is not also counted as backward.
+    fn isolated_not_backward() {
+        let b = Board::from_fen("4k3/8/8/4p3/8/3P4/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(backward_pawns(&b, Color::White), Bitboard::EMPTY);
+        assert_eq!(backward(&b), Score::DRAW);
+    }
+
+    #[test]
+    /// Test that a connected pawn duo scores higher than two isolated pawns on the same ranks.
+    fn connected_duo() {
+        let duo = Board::from_fen("4k3/8/8/8/3PP3/8/8/4K3 w - - 0 1").unwrap();
+        let isolated = Board::from_fen("4k3/8/8/8/2P2P2/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(connected(&duo), CONNECTED[3] * 2u8);
+        assert_eq!(connected(&isolated), Score::DRAW);
+        assert!(connected(&duo).blend(0.5) > connected(&isolated).blend(0.5));
+    }
+
+    #[test]
+    /// Test that a pawn chain rewards only the defended pawns, with advanced ones worth more.
+    fn connected_chain() {
+        let b = Board::from_fen("4k3/8/8/4P3/3P4/2P5/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            connected_pawns(&b, Color::White),
+            Bitboard::from_squares(&[Square::D4, Square::E5])
+        );
+        assert_eq!(connected(&b), CONNECTED[3] + CONNECTED[4]);
+        assert!(CONNECTED[4].blend(0.5) > CONNECTED[3].blend(0.5));
+
+        // the same structure for Black counts against White
+        let mirrored = Board::from_fen("4k3/8/2p5/3p4/4p3/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(connected(&mirrored), Score::DRAW - connected(&b));
+    }
+
+    #[test]
+    /// Test that Black's majorities count against White.
+    fn black_majority() {
+        let b = Board::from_fen("4k3/pp2pppp/8/8/8/8/PP3PP1/4K3 w - - 0 1").unwrap();
+        assert_eq!(pawn_majority(&b), Score::DRAW - WING_MAJORITY);
+    }
+}
@@ -24,17 +24,17 @@
 //! For instance, a knight is much more valuable near the center, so the PST value for a knight on
 //! rank 4 and file 3 is positive.
 
-use std::intrinsics::transmute;
+use std::io::{self, BufRead, BufReader, Read, Write};
 
 use crate::base::{Board, Color, Move, Piece, Square};
 
-use crate::engine::evaluate::Score;
+use crate::engine::evaluate::{Eval, Score};
 
 /// A lookup table for piece values.
 /// The outer index is the type of the piece (in order of Pawn, Knight, Bishop, Rook, Queen, and
 /// King) and the inner index is the square of the piece (from White's point of view), starting with
 /// A1 as the first index, then continuing on to B1, C1, and so on until H8 as index 63.
-type Pst = [[Score; 64]; Piece::NUM];
+pub type Pst = [[Score; 64]; Piece::NUM];
 
 #[must_use]
 /// Evaluate a board based on its PST value.
@@ -43,23 +43,116 @@ type Pst = [[Score; 64]; Piece::NUM];
 /// The first value in the return type is the midgame difference, and the second is the endgame
 /// difference.
 pub fn evaluate(board: &Board) -> Score {
+    evaluate_tables([&PST, &PST_BLACK], board)
+}
+
+#[must_use]
+/// Evaluate a board based on the PST values given by `tables` instead of the built-in `PST`.
+/// `tables` contains the table for White followed by its `mirror()` for Black, so that callers
+/// evaluating many boards with the same table only mirror it once.
+/// This is mostly useful for tuning, where the table is changed between evaluations.
+pub fn evaluate_with(board: &Board, tables: &[Pst; 2]) -> Score {
+    evaluate_tables([&tables[0], &tables[1]], board)
+}
+
+/// Evaluate a board using `tables`, which contains the table for White followed by the
+/// already-mirrored table for Black.
+fn evaluate_tables(tables: [&Pst; 2], board: &Board) -> Score {
     let mut score = Score::DRAW;
 
     for pt in Piece::ALL {
         for sq in board[pt] & board[Color::White] {
-            score += PST[pt as usize][sq as usize];
+            score += table_value(tables, pt, Color::White, sq);
         }
         for sq in board[pt] & board[Color::Black] {
-            // Invert the square that Black is on, since positional values are flipped (as pawns
-            // move the other way, etc)
-            let alt_sq = sq.opposite();
-            score -= PST[pt as usize][alt_sq as usize];
+            score -= table_value(tables, pt, Color::Black, sq);
         }
     }
 
     score
 }
 
+#[inline(always)]
+#[must_use]
+/// Get the PST value of a piece of type `pt` and color `color` standing on `sq`, from the point of
+/// view of that piece's color.
+/// Black's values are looked up in `PST_BLACK`, since positional values are flipped (as pawns move
+/// the other way, etc), so callers should never mirror squares themselves.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Color, Piece, Square};
+/// use tomato::engine::evaluate::pst::pst_value;
+///
+/// assert_eq!(
+///     pst_value(Piece::Knight, Color::Black, Square::G8),
+///     pst_value(Piece::Knight, Color::White, Square::G1),
+/// );
+/// ```
+pub fn pst_value(pt: Piece, color: Color, sq: Square) -> Score {
+    table_value([&PST, &PST_BLACK], pt, color, sq)
+}
+
+#[inline(always)]
+/// Get the value of a piece of type `pt` and color `color` standing on `sq`, where `tables`
+/// contains the table for White followed by the already-mirrored table for Black.
+fn table_value(tables: [&Pst; 2], pt: Piece, color: Color, sq: Square) -> Score {
+    tables[color as usize][pt as usize][sq as usize]
+}
+
+#[must_use]
+/// Flip a piece-square table vertically, so that it can be indexed directly from Black's point of
+/// view.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Piece, Square};
+/// use tomato::engine::evaluate::pst::{mirror, PST};
+///
+/// let flipped = mirror(&PST);
+/// assert_eq!(
+///     flipped[Piece::Pawn as usize][Square::E7 as usize],
+///     PST[Piece::Pawn as usize][Square::E2 as usize],
+/// );
+/// ```
+pub const fn mirror(table: &Pst) -> Pst {
+    let mut flipped = [[Score::DRAW; 64]; Piece::NUM];
+    let mut pt = 0;
+    while pt < Piece::NUM {
+        let mut sq = 0;
+        while sq < 64 {
+            // flipping the rank is the same as `Square::opposite()`
+            flipped[pt][sq ^ 0x38] = table[pt][sq];
+            sq += 1;
+        }
+        pt += 1;
+    }
+
+    flipped
+}
+
+#[must_use]
+/// Build a piece-square table from `(midgame, endgame)` pairs of centipawn values, laid out the
+/// same way as a `Pst`.
+/// This lets a table be written out as plain numbers while still being evaluated at compile time.
+pub const fn from_pairs(pairs: &[[(i16, i16); 64]; Piece::NUM]) -> Pst {
+    let mut table = [[Score::DRAW; 64]; Piece::NUM];
+    let mut pt = 0;
+    while pt < Piece::NUM {
+        let mut sq = 0;
+        while sq < 64 {
+            let (mg, eg) = pairs[pt][sq];
+            table[pt][sq] = Score::centipawns(mg, eg);
+            sq += 1;
+        }
+        pt += 1;
+    }
+
+    table
+}
+
 #[must_use]
 /// Get the difference in PST value which would be generated by making the move
 /// `m` on `board`.
@@ -70,56 +163,220 @@ pub fn evaluate(board: &Board) -> Score {
 ///
 /// This function will panic if the given move is invalid.
 pub fn delta(board: &Board, m: Move) -> Score {
+    delta_tables([&PST, &PST_BLACK], board, m)
+}
+
+#[must_use]
+/// Get the difference in PST value given by `tables` which would be generated by making the move
+/// `m` on `board`.
+/// As in `evaluate_with()`, `tables` contains the table for White followed by its mirror for
+/// Black.
+/// The result is from the point of view of the player making the move, as in `delta()`.
+///
+/// # Panics
+///
+/// This function will panic if the given move is invalid.
+pub fn delta_with(board: &Board, m: Move, tables: &[Pst; 2]) -> Score {
+    delta_tables([&tables[0], &tables[1]], board, m)
+}
+
+/// Get the difference in PST value for making `m` on `board`, where `tables` contains the table
+/// for White followed by the already-mirrored table for Black.
+fn delta_tables(tables: [&Pst; 2], board: &Board, m: Move) -> Score {
     let from_sq = m.from_square();
     let to_sq = m.to_square();
+    let player = board.player;
     let mover_type = board.type_at_square(m.from_square()).unwrap();
-    let mover_idx = mover_type as usize;
     let end_type = match m.promote_type() {
         Some(pt) => pt,
         None => mover_type,
     };
-    let end_idx = end_type as usize;
-    let (from_alt, to_alt) = match board.player {
-        Color::White => (from_sq, to_sq),
-        Color::Black => (from_sq.opposite(), to_sq.opposite()),
-    };
-    let (from_idx, to_idx) = (from_alt as usize, to_alt as usize);
 
     // you always lose the value of the square you moved from
-    let mut delta = PST[end_idx][to_idx] - PST[mover_idx][from_idx];
+    let mut delta = table_value(tables, end_type, player, to_sq)
+        - table_value(tables, mover_type, player, from_sq);
 
-    if board[!board.player].contains(m.to_square()) {
+    if board[!player].contains(m.to_square()) {
         // conventional capture
-        let to_opposite_idx = to_alt.opposite() as usize;
-        let capturee_idx = board.type_at_square(to_sq).unwrap() as usize;
-        delta += PST[capturee_idx][to_opposite_idx];
+        let capturee_type = board.type_at_square(to_sq).unwrap();
+        delta += table_value(tables, capturee_type, !player, to_sq);
     }
 
     if m.is_en_passant() {
-        let to_opposite_idx = (to_alt - Color::White.pawn_direction()).opposite() as usize;
-        delta += PST[Piece::Pawn as usize][to_opposite_idx];
+        // the captured pawn is one rank behind the target square, from the mover's point of view
+        let capturee_sq = match player {
+            Color::White => to_sq.try_offset(0, -1),
+            Color::Black => to_sq.try_offset(0, 1),
+        }
+        .unwrap();
+        delta += table_value(tables, Piece::Pawn, !player, capturee_sq);
     }
 
-    if m.is_castle() {
-        let is_queen_castle = to_sq.file() == 2;
-        let (rook_from_idx, rook_to_idx) = if is_queen_castle {
-            (Square::A1 as usize, Square::D1 as usize)
-        } else {
-            (Square::H1 as usize, Square::F1 as usize)
+    if let Some((rook_from_sq, rook_to_sq)) = board.castle_rook_squares(m) {
+        delta += table_value(tables, Piece::Rook, player, rook_to_sq)
+            - table_value(tables, Piece::Rook, player, rook_from_sq);
+    }
+
+    delta
+}
+
+/// Load a piece-square table from `reader`.
+/// The table is given as text, with one `(midgame, endgame)` pair per line, written as two
+/// integers separated by whitespace.
+/// Pairs are given in the same order as in `Pst`: all 64 squares for knights (from A1 to H8), then
+/// for bishops, and so on, following the order of `Piece::ALL`.
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// # Errors
+///
+/// This function will return an error if `reader` could not be read, if a line does not contain
+/// exactly two integers, or if there are not exactly `6 * 64` pairs.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::engine::evaluate::pst::{load_pst, save_pst, PST};
+///
+/// let mut buf = Vec::new();
+/// save_pst(&PST, &mut buf).unwrap();
+/// assert_eq!(load_pst(buf.as_slice()).unwrap(), PST);
+/// ```
+pub fn load_pst(reader: impl Read) -> io::Result<Pst> {
+    let invalid = |msg: &'static str| io::Error::new(io::ErrorKind::InvalidData, msg);
+    let mut table = [[Score::DRAW; 64]; Piece::NUM];
+    let mut n_entries = 0;
+
+    for line_result in BufReader::new(reader).lines() {
+        let line = line_result?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut tokens = line.split_whitespace().map(str::parse::<i16>);
+        let (Some(Ok(mg)), Some(Ok(eg)), None) = (tokens.next(), tokens.next(), tokens.next())
+        else {
+            return Err(invalid("each PST entry must be two integers"));
         };
+        if n_entries >= 64 * Piece::NUM {
+            return Err(invalid("too many PST entries"));
+        }
+        table[n_entries / 64][n_entries % 64] = Score::centipawns(mg, eg);
+        n_entries += 1;
+    }
 
-        delta += PST[Piece::Rook as usize][rook_to_idx] - PST[Piece::Rook as usize][rook_from_idx];
+    if n_entries < 64 * Piece::NUM {
+        return Err(invalid("too few PST entries"));
     }
 
-    delta
+    Ok(table)
+}
+
+/// Write a piece-square table to `writer` in the format read by `load_pst()`.
+///
+/// # Errors
+///
+/// This function will return an error if `writer` could not be written to.
+pub fn save_pst(table: &Pst, mut writer: impl Write) -> io::Result<()> {
+    for pt in Piece::ALL {
+        writeln!(writer, "# {pt}")?;
+        for score in table[pt as usize] {
+            writeln!(
+                writer,
+                "{} {}",
+                score.midgame().centipawn_val(),
+                score.endgame().centipawn_val()
+            )?;
+        }
+    }
+
+    Ok(())
 }
 
+#[allow(clippy::missing_panics_doc)]
+/// Check that `table` satisfies the invariants required of any piece-square table.
+/// Currently, the only required invariant is that every pawn entry on ranks 1 and 8 is zero,
+/// since no pawn can ever stand there and a nonzero value usually means the table was transposed
+/// or flipped.
+/// Left-right symmetry is not required, since tuned tables may be intentionally asymmetric; use
+/// `check_symmetry()` to look for that.
+///
+/// # Errors
+///
+/// This function will return an error describing the first violated invariant, if any.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::engine::evaluate::pst::{validate_pst, PST};
+///
+/// assert!(validate_pst(&PST).is_ok());
+/// ```
+pub fn validate_pst(table: &Pst) -> Result<(), String> {
+    for rank in [0, 7] {
+        for file in 0..8 {
+            let sq = Square::new(rank, file).unwrap();
+            let score = table[Piece::Pawn as usize][sq as usize];
+            if score != Score::DRAW {
+                return Err(format!(
+                    "pawn entry on {sq} is {score}, but pawns can never stand there"
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::missing_panics_doc)]
+/// Check whether every entry of `table` is within `tolerance` of its left-right mirror (for
+/// instance, comparing C4 to F4), in both the midgame and endgame.
+/// This is only advisory: an asymmetric table is still valid, but a large asymmetry is often an
+/// unintended bias worth looking at.
+///
+/// # Errors
+///
+/// This function will return an error describing the first pair of entries which differ by more
+/// than `tolerance`.
+pub fn check_symmetry(table: &Pst, tolerance: Eval) -> Result<(), String> {
+    let tolerance = tolerance.centipawn_val().unsigned_abs();
+    for pt in Piece::ALL {
+        for rank in 0..8 {
+            for file in 0..4 {
+                let sq = Square::new(rank, file).unwrap();
+                let mirror_sq = Square::new(rank, 7 - file).unwrap();
+                let score = table[pt as usize][sq as usize];
+                let mirror = table[pt as usize][mirror_sq as usize];
+                let mg_diff = score
+                    .midgame()
+                    .centipawn_val()
+                    .abs_diff(mirror.midgame().centipawn_val());
+                let eg_diff = score
+                    .endgame()
+                    .centipawn_val()
+                    .abs_diff(mirror.endgame().centipawn_val());
+                if mg_diff > tolerance || eg_diff > tolerance {
+                    return Err(format!(
+                        "{pt} entries on {sq} ({score}) and {mirror_sq} ({mirror}) differ by more \
+                         than {tolerance}"
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The main piece-square table, flipped vertically so that it can be indexed directly by the
+/// squares of Black's pieces.
+pub const PST_BLACK: Pst = mirror(&PST);
+
 #[rustfmt::skip] // rustfmt likes to throw a million newlines in this
 /// The main piece-square table. 
-/// Evaluations are paired together as (midgame, endgame) to improve cache-friendliness. 
-/// The indexing order of this table has its primary index as pieces, the secondary index as 
-/// squares, and the innermost index as 0 for midgame and 1 for endgame.
-pub const PST: Pst = unsafe { transmute([
+/// Evaluations are written as (midgame, endgame) pairs and packed into a `Score` for each entry. 
+/// The indexing order of this table has its primary index as pieces and the secondary index as 
+/// squares.
+pub const PST: Pst = from_pairs(&[
     [ // N
         (-11i16, -14i16), (0, -14), (-35, -8), (-11, 1), (0, 0), (-21, -5), (0, -6), (-55, -27), 
         (-55, -46), (-42, -26), (-16, -8), (3, 2), (4, 5), (-10, -32), (-27, -28), (-9, -50), 
@@ -161,7 +418,7 @@ pub const PST: Pst = unsafe { transmute([
         (-28, -14), (0, 2), (10, 1), (0, 5), (36, 0), (8, -6), (17, -9), (23, -22), 
     ],
     [ // P
-        (2, 2), (0, 0), (0, 0), (0, 0), (0, 0), (0, 0), (0, 0), (0, 0), 
+        (0, 0), (0, 0), (0, 0), (0, 0), (0, 0), (0, 0), (0, 0), (0, 0), 
         (-7, 0), (4, 2), (-15, 0), (-11, 2), (0, 8), (13, 0), (20, 2), (-10, -3), 
         (-7, -8), (-4, 1), (-4, -5), (-5, 0), (0, -1), (0, -6), (6, -1), (-3, -13), 
         (-8, 3), (-2, 9), (0, 0), (9, -5), (9, -4), (-1, -4), (-3, 1), (-13, -9), 
@@ -180,13 +437,140 @@ pub const PST: Pst = unsafe { transmute([
         (0, -8), (22, 5), (14, 0), (14, 0), (17, 0), (24, 9), (25, 3), (21, 4), 
         (-30, -33), (-1, -32), (-5, -17), (-26, -4), (-20, 1), (-16, 12), (28, 6), (0, -9), 
     ],
-]) };
+]);
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use crate::base::{game::Game, movegen::GenMode};
+    use crate::base::{game::Game, movegen::GenMode, Bitboard};
+
+    #[test]
+    /// Test that `PST_BLACK` is the vertical flip of `PST`.
+    fn black_table_mirrors() {
+        for pt in Piece::ALL {
+            for sq in Bitboard::ALL {
+                assert_eq!(
+                    PST_BLACK[pt as usize][sq as usize],
+                    PST[pt as usize][sq.opposite() as usize]
+                );
+            }
+        }
+    }
+
+    #[test]
+    /// Test that the built-in table satisfies the required invariants, and that a table with a
+    /// pawn value on the back rank is rejected.
+    fn validate_builtin() {
+        assert!(validate_pst(&PST).is_ok());
+
+        let mut table = PST;
+        table[Piece::Pawn as usize][Square::D8 as usize] = Score::centipawns(5, 5);
+        assert!(validate_pst(&table).is_err());
+    }
+
+    #[test]
+    /// Test that the symmetry check respects its tolerance.
+    fn symmetry_tolerance() {
+        assert!(check_symmetry(&PST, Eval::centipawns(1000)).is_ok());
+
+        let mut table = [[Score::DRAW; 64]; Piece::NUM];
+        assert!(check_symmetry(&table, Eval::DRAW).is_ok());
+        table[Piece::Knight as usize][Square::C3 as usize] = Score::centipawns(10, 0);
+        assert!(check_symmetry(&table, Eval::centipawns(10)).is_ok());
+        assert!(check_symmetry(&table, Eval::centipawns(9)).is_err());
+    }
+
+    #[test]
+    /// Test that the built-in table survives a round trip through `save_pst()` and `load_pst()`,
+    /// and that evaluating with the loaded table gives the same results.
+    fn save_load_round_trip() {
+        let mut buf = Vec::new();
+        save_pst(&PST, &mut buf).unwrap();
+        let table = load_pst(buf.as_slice()).unwrap();
+        assert_eq!(table, PST);
+        let tables = [table, mirror(&table)];
+
+        let g =
+            Game::from_fen("r1bq1rk1/pppp1ppp/2n2n2/2b1p3/2B1P3/3P1N2/PPP2PPP/RNBQ1RK1 w - - 5 6")
+                .unwrap();
+        assert_eq!(evaluate_with(g.board(), &tables), evaluate(g.board()));
+        for m in g.get_moves::<{ GenMode::All }>() {
+            assert_eq!(delta_with(g.board(), m, &tables), delta(g.board(), m));
+        }
+    }
+
+    #[test]
+    /// Test that malformed tables are rejected.
+    fn load_malformed() {
+        let mut buf = Vec::new();
+        save_pst(&PST, &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+
+        // missing the last entry
+        let short = &text[..text.trim_end().rfind('\n').unwrap()];
+        assert!(load_pst(short.as_bytes()).is_err());
+
+        // one entry too many
+        let long = format!("{text}0 0\n");
+        assert!(load_pst(long.as_bytes()).is_err());
+
+        // not a pair of integers
+        assert!(load_pst(text.replacen("0 0", "0 x", 1).as_bytes()).is_err());
+        assert!(load_pst(text.replacen("0 0", "0 0 0", 1).as_bytes()).is_err());
+    }
+
+    #[test]
+    /// Test that the delta of a castle with the rook starting off the corner, as in Chess960,
+    /// matches the value from scratch.
+    /// The positions are Chess960 start positions once the pieces between the king and the rook
+    /// have moved away.
+    fn delta_castles_960() {
+        for (fen, m, rook_from_sq, rook_to_sq) in [
+            // NRKBQRBN: the king stays on C1 and the rook comes from B1
+            (
+                "nrk1qrbn/pppppppp/8/8/8/8/PPPPPPPP/NRK1QRBN w KQkq - 0 1",
+                Move::castling(Square::C1, Square::C1),
+                Square::B1,
+                Square::D1,
+            ),
+            // RNQBBKNR: the king moves one file and the rook lands on its starting square
+            (
+                "rnqbbk1r/pppppppp/8/8/8/8/PPPPPPPP/RNQBBK1R w KQkq - 0 1",
+                Move::castling(Square::F1, Square::G1),
+                Square::H1,
+                Square::F1,
+            ),
+            // RKBBNNQR: the king moves one file toward the queenside
+            (
+                "rk2nnqr/pppppppp/8/8/8/8/PPPPPPPP/RK2NNQR b KQkq - 0 1",
+                Move::castling(Square::B8, Square::C8),
+                Square::A8,
+                Square::D8,
+            ),
+            // the castling rook is named by its file, and a second rook stands beyond it
+            (
+                "1k6/8/8/8/8/8/8/1KR4R w C - 0 1",
+                Move::castling(Square::B1, Square::G1),
+                Square::C1,
+                Square::F1,
+            ),
+        ] {
+            let mut b = Board::from_fen(fen).unwrap();
+            let player = b.player;
+            assert_eq!(b.castle_rook_squares(m), Some((rook_from_sq, rook_to_sq)));
+            let orig_eval = evaluate(&b);
+            let new_eval = match player {
+                Color::White => orig_eval + delta(&b, m),
+                Color::Black => orig_eval - delta(&b, m),
+            };
+            b.make_move(m);
+            assert_eq!(b.king_sqs[player as usize], m.to_square());
+            assert!(b.pieces_of_type(player, Piece::Rook).contains(rook_to_sq));
+            assert!(!b.pieces_of_type(player, Piece::Rook).contains(rook_from_sq));
+            assert_eq!(new_eval, evaluate(&b));
+        }
+    }
 
     fn delta_helper(fen: &str) {
         let mut g = Game::from_fen(fen).unwrap();
@@ -203,6 +587,55 @@ mod tests {
         }
     }
 
+    #[test]
+    /// Test that the PST accessor mirrors Black's squares.
+    fn pst_value_mirrors() {
+        assert_eq!(
+            pst_value(Piece::Pawn, Color::Black, Square::E7),
+            PST[Piece::Pawn as usize][Square::E2 as usize]
+        );
+        assert_eq!(
+            pst_value(Piece::Pawn, Color::White, Square::E2),
+            PST[Piece::Pawn as usize][Square::E2 as usize]
+        );
+    }
+
+    #[test]
+    /// Test that evaluating through the PST accessor matches indexing the table directly.
+    fn pst_value_matches_direct() {
+        /// Evaluate a board by indexing `PST` directly.
+        fn direct_evaluate(board: &Board) -> Score {
+            let mut score = Score::DRAW;
+            for pt in Piece::ALL {
+                for sq in board[pt] & board[Color::White] {
+                    score += PST[pt as usize][sq as usize];
+                }
+                for sq in board[pt] & board[Color::Black] {
+                    score -= PST[pt as usize][sq.opposite() as usize];
+                }
+            }
+            score
+        }
+
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r1bq1b1r/ppp2kpp/2n5/3np3/2B5/8/PPPP1PPP/RNBQK2R w KQ - 0 7",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r4bkr/pPpq2pp/2n1b3/3n4/2BPp3/2P5/1P3PPP/RNBQK2R w KQ - 1 13",
+        ] {
+            let b = Board::from_fen(fen).unwrap();
+            assert_eq!(evaluate(&b), direct_evaluate(&b));
+        }
+    }
+
+    #[test]
+    /// Test that castling deltas match the value from scratch, for both colors.
+    fn delta_castles() {
+        delta_helper("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+        delta_helper("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1");
+    }
+
     #[test]
     /// Test that adding deltas matches the same result as taking the PST value from scratch.
     fn pst_delta_equals_base_result() {
@@ -218,4 +651,14 @@ mod tests {
     fn delta_promotion() {
         delta_helper("r4bkr/pPpq2pp/2n1b3/3n4/2BPp3/2P5/1P3PPP/RNBQK2R w KQ - 1 13");
     }
+
+    #[test]
+    /// Test that the delta of an en passant capture matches the value from scratch, for both
+    /// colors.
+    fn delta_en_passant() {
+        // exf6 is en passant
+        delta_helper("rnbqkb1r/ppppp1pp/7n/4Pp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3");
+        // dxe3 is en passant
+        delta_helper("rnbqkbnr/ppp1pppp/8/8/3pP3/5N2/PPPP1PPP/RNBQKB1R b KQkq e3 0 3");
+    }
 }
@@ -0,0 +1,193 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Rook placement.
+//!
+//! A rook on the enemy's second rank (the seventh from its own side) attacks pawns which have not
+//! yet moved and can cut the enemy king off on its back rank.
+//! This is especially strong when both rooks get there, or when the enemy king is still stuck on
+//! the back rank behind them.
+//!
+//! Rooks also work best together: two rooks which defend each other along a file or rank can
+//! trade one another in and double their pressure.
+
+use crate::{
+    base::{Bitboard, Board, Color, Piece, MAGIC},
+    engine::evaluate::Score,
+};
+
+/// The bonus for each rook on the seventh rank.
+pub const ROOK_ON_SEVENTH: Score = Score::centipawns(12, 24);
+
+/// The extra bonus for having both rooks doubled on the seventh rank.
+pub const DOUBLED_ON_SEVENTH: Score = Score::centipawns(16, 20);
+
+/// The extra bonus for each rook on the seventh rank when the enemy king is on its back rank.
+pub const KING_ON_EIGHTH: Score = Score::centipawns(8, 14);
+
+/// The bonus for a pair of rooks doubled on a file with nothing between them.
+pub const DOUBLED_ROOKS: Score = Score::centipawns(16, 10);
+
+/// The bonus for a pair of rooks connected along a rank with nothing between them.
+pub const CONNECTED_ROOKS: Score = Score::centipawns(8, 4);
+
+/// The seventh rank from each side's point of view, indexed by color.
+const SEVENTH_RANK: [Bitboard; 2] = [Bitboard::RANKS[6], Bitboard::RANKS[1]];
+
+/// The eighth rank from each side's point of view (the enemy's back rank), indexed by color.
+const EIGHTH_RANK: [Bitboard; 2] = [Bitboard::RANKS[7], Bitboard::RANKS[0]];
+
+#[must_use]
+/// Evaluate a position based on the placement of each side's rooks: on the seventh rank, and
+/// relative to one another.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::rooks::{evaluate, KING_ON_EIGHTH, ROOK_ON_SEVENTH};
+///
+/// let b = Board::from_fen("4k3/R7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+/// assert_eq!(evaluate(&b), ROOK_ON_SEVENTH + KING_ON_EIGHTH);
+/// ```
+pub fn evaluate(b: &Board) -> Score {
+    seventh_for(b, Color::White) - seventh_for(b, Color::Black) + rook_pairs(b)
+}
+
+#[must_use]
+/// Evaluate the rooks of each side which defend one another, with no pieces between them.
+/// Rooks doubled on a file get a larger bonus than rooks connected along a rank.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::rooks::{rook_pairs, DOUBLED_ROOKS};
+///
+/// let b = Board::from_fen("4k3/8/8/8/8/8/3R4/3RK3 w - - 0 1").unwrap();
+/// assert_eq!(rook_pairs(&b), DOUBLED_ROOKS);
+/// ```
+pub fn rook_pairs(b: &Board) -> Score {
+    rook_pairs_for(b, Color::White) - rook_pairs_for(b, Color::Black)
+}
+
+/// Get the rook pair bonus for one color, from that color's point of view.
+fn rook_pairs_for(b: &Board, color: Color) -> Score {
+    let rooks = b[Piece::Rook] & b[color];
+    let occupancy = b.occupancy();
+    let mut score = Score::DRAW;
+    for sq in rooks {
+        for other in MAGIC.rook_attacks(occupancy, sq) & rooks {
+            // only count each pair once
+            if (other as u8) < (sq as u8) {
+                continue;
+            }
+            score += if other.file() == sq.file() {
+                DOUBLED_ROOKS
+            } else {
+                CONNECTED_ROOKS
+            };
+        }
+    }
+
+    score
+}
+
+/// Get the seventh-rank bonus for one color, from that color's point of view.
+fn seventh_for(b: &Board, color: Color) -> Score {
+    let rooks = b[Piece::Rook] & b[color] & SEVENTH_RANK[color as usize];
+    if rooks.is_empty() {
+        return Score::DRAW;
+    }
+
+    let mut per_rook = ROOK_ON_SEVENTH;
+    if !(b[Piece::King] & b[!color] & EIGHTH_RANK[color as usize]).is_empty() {
+        per_rook += KING_ON_EIGHTH;
+    }
+    let mut score = per_rook * rooks.len();
+    if rooks.len() >= 2 {
+        score += DOUBLED_ON_SEVENTH;
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test that a rook lifted to the seventh rank scores higher than a rook on the third.
+    fn seventh_beats_third() {
+        let seventh = Board::from_fen("6k1/R4ppp/8/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+        let third = Board::from_fen("6k1/5ppp/8/8/8/R7/5PPP/6K1 w - - 0 1").unwrap();
+        assert_eq!(evaluate(&seventh), ROOK_ON_SEVENTH + KING_ON_EIGHTH);
+        assert_eq!(evaluate(&third), Score::DRAW);
+        for phase in [0., 0.5, 1.] {
+            assert!(evaluate(&seventh).blend(phase) > evaluate(&third).blend(phase));
+        }
+    }
+
+    #[test]
+    /// Test that the king and doubling bonuses are only given when they apply.
+    fn extra_bonuses() {
+        // the Black king has left its back rank
+        let king_up = Board::from_fen("8/R4ppp/6k1/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+        assert_eq!(evaluate(&king_up), ROOK_ON_SEVENTH);
+
+        let doubled = Board::from_fen("6k1/RR3ppp/8/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+        assert_eq!(
+            evaluate(&doubled),
+            (ROOK_ON_SEVENTH + KING_ON_EIGHTH) * 2u8 + DOUBLED_ON_SEVENTH + CONNECTED_ROOKS
+        );
+    }
+
+    #[test]
+    /// Test that rooks doubled on an open file score higher than rooks on separate files, and
+    /// that doubling is worth more than connecting along a rank.
+    fn doubled_rooks() {
+        let doubled = Board::from_fen("4k3/pp3ppp/8/8/8/8/PP1R1PPP/3R2K1 w - - 0 1").unwrap();
+        let separate = Board::from_fen("4k3/pp3ppp/8/8/8/8/PPR2PPP/3R2K1 w - - 0 1").unwrap();
+        let connected = Board::from_fen("4k3/pp3ppp/8/8/8/8/PP3PPP/2RR2K1 w - - 0 1").unwrap();
+        assert_eq!(rook_pairs(&doubled), DOUBLED_ROOKS);
+        assert_eq!(rook_pairs(&separate), Score::DRAW);
+        assert_eq!(rook_pairs(&connected), CONNECTED_ROOKS);
+        for phase in [0., 0.5, 1.] {
+            assert!(rook_pairs(&doubled).blend(phase) > rook_pairs(&separate).blend(phase));
+            assert!(rook_pairs(&doubled).blend(phase) > rook_pairs(&connected).blend(phase));
+        }
+    }
+
+    #[test]
+    /// Test that rooks with a piece between them are not connected.
+    fn blocked_rooks() {
+        let b = Board::from_fen("3rk3/8/8/8/8/3N4/8/3R2K1 w - - 0 1").unwrap();
+        assert_eq!(rook_pairs(&b), Score::DRAW);
+        let b = Board::from_fen("r2qk2r/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(rook_pairs(&b), Score::DRAW);
+        let b = Board::from_fen("r3k2r/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(rook_pairs(&b), Score::DRAW);
+    }
+
+    #[test]
+    /// Test that a Black rook on its seventh rank counts against White.
+    fn black_seventh() {
+        let b = Board::from_fen("6k1/5ppp/8/8/8/8/r4PPP/6K1 w - - 0 1").unwrap();
+        assert_eq!(evaluate(&b), Score::DRAW - ROOK_ON_SEVENTH - KING_ON_EIGHTH);
+    }
+}
@@ -0,0 +1,150 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Endgame scaling.
+//!
+//! Some endgames are much more drawish than their material balance would suggest.
+//! Rather than adding a bonus or penalty, these endgames scale the endgame half of the evaluation
+//! toward zero.
+
+use crate::{
+    base::{Bitboard, Board, Color, Piece},
+    engine::evaluate::Score,
+};
+
+/// The scale factor for an endgame with no special drawishness.
+pub const SCALE_NORMAL: u8 = 64;
+
+/// The scale factor for an ending where each side has a single bishop, on opposite colors.
+pub const SCALE_OPPOSITE_BISHOPS: u8 = 32;
+
+/// The scale factor for a single-bishop ending with every pawn on the same wing.
+pub const SCALE_ONE_WING_BISHOPS: u8 = 44;
+
+/// The light squares of the board.
+const LIGHT_SQUARES: Bitboard = Bitboard::new(0x55AA_55AA_55AA_55AA);
+
+#[must_use]
+/// Get the factor by which the endgame half of the evaluation should be scaled, out of
+/// `SCALE_NORMAL`.
+/// A factor of `SCALE_NORMAL` leaves the evaluation untouched, while 0 would make it a dead draw.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::scale::{scale_factor, SCALE_NORMAL};
+///
+/// assert_eq!(scale_factor(&Board::new()), SCALE_NORMAL);
+/// ```
+pub fn scale_factor(board: &Board) -> u8 {
+    let pawns = board[Piece::Pawn];
+    let bishops = board[Piece::Bishop];
+    let others = board[Piece::Knight] | board[Piece::Rook] | board[Piece::Queen];
+
+    // in a single-bishop ending with all pawns on one wing, the defending king can sit in front of
+    // the pawns
+    let single_bishops = others.is_empty()
+        && bishops.len() == 2
+        && !(bishops & board[Color::White]).is_empty()
+        && !(bishops & board[Color::Black]).is_empty();
+
+    // opposite-colored bishops can never contest each other's squares, so the defender can
+    // blockade on the squares its bishop covers
+    if single_bishops && (bishops & LIGHT_SQUARES).len() == 1 {
+        return SCALE_OPPOSITE_BISHOPS;
+    }
+
+    let queenside_half =
+        Bitboard::FILES[0] | Bitboard::FILES[1] | Bitboard::FILES[2] | Bitboard::FILES[3];
+    let one_wing = (pawns & queenside_half).is_empty() || (pawns & !queenside_half).is_empty();
+    if single_bishops && !pawns.is_empty() && one_wing {
+        return SCALE_ONE_WING_BISHOPS;
+    }
+
+    SCALE_NORMAL
+}
+
+#[must_use]
+/// Scale the endgame half of a score by the scale factor of `board`.
+pub fn scale(score: Score, board: &Board) -> Score {
+    scale_by(score, scale_factor(board))
+}
+
+#[must_use]
+/// Scale the endgame half of a score by `factor`, out of `SCALE_NORMAL`.
+pub fn scale_by(score: Score, factor: u8) -> Score {
+    if factor == SCALE_NORMAL {
+        return score;
+    }
+
+    Score::new(
+        score.midgame(),
+        score.endgame() * (f32::from(factor) / f32::from(SCALE_NORMAL)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::evaluate::Eval;
+
+    #[test]
+    /// Test that a bishop ending with every pawn on the kingside is scaled further toward a draw
+    /// than one with pawns on both wings.
+    fn one_wing_more_drawish() {
+        let one_wing = Board::from_fen("2b1k3/5pp1/8/8/8/5PPP/8/4KB2 w - - 0 1").unwrap();
+        let two_wings = Board::from_fen("2b1k3/p4p2/8/8/8/P4PP1/8/4KB2 w - - 0 1").unwrap();
+        assert_eq!(one_wing[Piece::Pawn].len(), two_wings[Piece::Pawn].len());
+        assert!(scale_factor(&one_wing) < scale_factor(&two_wings));
+
+        let score = Score::centipawns(100, 100);
+        assert!(scale(score, &one_wing).blend(0.) < scale(score, &two_wings).blend(0.));
+        assert!(scale(score, &one_wing).blend(0.) > Eval::DRAW);
+    }
+
+    #[test]
+    /// Test that an opposite-colored bishop ending is scaled further toward a draw than the same
+    /// material with same-colored bishops.
+    fn opposite_bishops_drawish() {
+        // White is a pawn up in both
+        let opposite = Board::from_fen("4k3/p4pb1/8/8/8/P4PP1/6B1/4K3 w - - 0 1").unwrap();
+        let same = Board::from_fen("4k3/p4p2/6b1/8/8/P4PP1/6B1/4K3 w - - 0 1").unwrap();
+        assert_eq!(scale_factor(&opposite), SCALE_OPPOSITE_BISHOPS);
+        assert_eq!(scale_factor(&same), SCALE_NORMAL);
+
+        let score = Score::centipawns(100, 100);
+        assert!(scale(score, &opposite).blend(0.) < scale(score, &same).blend(0.));
+        assert!(scale(score, &opposite).blend(0.) > Eval::DRAW);
+    }
+
+    #[test]
+    /// Test that only the endgame half of a score is scaled.
+    fn midgame_untouched() {
+        let b = Board::from_fen("2b1k3/5pp1/8/8/8/5PPP/8/4KB2 w - - 0 1").unwrap();
+        let score = Score::centipawns(100, 100);
+        assert_eq!(scale(score, &b).midgame(), score.midgame());
+    }
+
+    #[test]
+    /// Test that endings with other pieces are not scaled.
+    fn other_pieces_normal() {
+        let b = Board::from_fen("2b1k3/5pp1/8/8/8/5PPP/8/3RKB2 w - - 0 1").unwrap();
+        assert_eq!(scale_factor(&b), SCALE_NORMAL);
+    }
+}
@@ -0,0 +1,168 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Games with an incrementally-tracked PST score.
+//!
+//! Computing the piece-square table score of a position from scratch means visiting every piece on
+//! the board, but a single move only changes the squares of one or two pieces.
+//! A `ScoredGame` wraps a `Game` and keeps the PST score of every position in its history, updating
+//! it with `pst::delta()` as moves are made and discarding it as they are undone.
+
+use crate::base::{game::Game, Board, Color, Move};
+
+use super::{pst, Score};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A game which tracks the PST score of its current position as moves are made and undone.
+pub struct ScoredGame {
+    /// The game being tracked.
+    game: Game,
+    /// The PST score of each position in the history of `game`, from White's point of view.
+    /// The last element is the score of the current position.
+    pst_scores: Vec<Score>,
+}
+
+impl ScoredGame {
+    #[must_use]
+    /// Construct a new `ScoredGame` tracking `game`.
+    /// The PST score of the current position is computed from scratch.
+    pub fn new(game: Game) -> ScoredGame {
+        ScoredGame {
+            pst_scores: vec![pst::evaluate(game.board())],
+            game,
+        }
+    }
+
+    /// Construct a new `ScoredGame` using the Forsyth-Edwards notation description of its
+    /// position.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an `Err` if the FEN string is invalid.
+    pub fn from_fen(fen: &str) -> Result<ScoredGame, &'static str> {
+        Ok(ScoredGame::new(Game::from_fen(fen)?))
+    }
+
+    /// Make a move, assuming said move is legal, and update the PST score to match.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if `m` is not a legal move.
+    pub fn make_move(&mut self, m: Move) {
+        let pst_delta = pst::delta(self.board(), m);
+        self.make_move_with_delta(m, pst_delta);
+    }
+
+    /// Make a move, where `pst_delta` is the value of `pst::delta()` for the move, from the point
+    /// of view of the player making it.
+    /// This avoids recomputing the delta when the caller has already computed it.
+    pub(crate) fn make_move_with_delta(&mut self, m: Move, pst_delta: Score) {
+        let score = self.pst_score();
+        self.pst_scores.push(match self.board().player {
+            Color::White => score + pst_delta,
+            Color::Black => score - pst_delta,
+        });
+        self.game.make_move(m);
+    }
+
+    /// Undo the most recent move, along with its PST score.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an `Err` if the game has no more moves left to undo, in which case
+    /// the game is left unchanged.
+    pub fn undo(&mut self) -> Result<Move, &'static str> {
+        let m = self.game.undo()?;
+        self.pst_scores.pop();
+        Ok(m)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    /// Get the PST score of the current position, from White's point of view.
+    /// This is the same as `pst::evaluate()` of the current board, but costs nothing to compute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::base::{Move, Square};
+    /// use tomato::engine::evaluate::{pst, scored::ScoredGame};
+    ///
+    /// let mut g = ScoredGame::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+    /// g.make_move(Move::normal(Square::E2, Square::E4));
+    /// assert_eq!(g.pst_score(), pst::evaluate(g.board()));
+    /// ```
+    pub fn pst_score(&self) -> Score {
+        *self.pst_scores.last().unwrap()
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Get the game being tracked.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    #[inline(always)]
+    #[must_use]
+    /// Get the position representing the current state of the game.
+    pub fn board(&self) -> &Board {
+        self.game.board()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::movegen::GenMode;
+
+    #[test]
+    /// Test that the incrementally-updated PST score always matches the PST score computed from
+    /// scratch, through a long random sequence of moves and undos.
+    fn pst_score_tracks() {
+        let rng = fastrand::Rng::with_seed(54321);
+        let mut g = ScoredGame::new(Game::new());
+        assert_eq!(g.pst_score(), pst::evaluate(g.board()));
+        for _ in 0..400 {
+            let moves = g.game().get_moves::<{ GenMode::All }>();
+            if moves.is_empty() || (g.game().len() > 1 && rng.u8(..4) == 0) {
+                if g.undo().is_err() {
+                    break;
+                }
+            } else {
+                g.make_move(moves[rng.usize(..moves.len())]);
+            }
+            assert_eq!(g.pst_score(), pst::evaluate(g.board()));
+        }
+
+        // unwind everything
+        while g.undo().is_ok() {
+            assert_eq!(g.pst_score(), pst::evaluate(g.board()));
+        }
+        assert_eq!(g.pst_scores.len(), 1);
+    }
+
+    #[test]
+    /// Test that a game loaded from FEN starts with its PST score computed from scratch.
+    fn from_fen_recomputes() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let g = ScoredGame::from_fen(fen).unwrap();
+        assert_eq!(g.pst_score(), pst::evaluate(&Board::from_fen(fen).unwrap()));
+    }
+}
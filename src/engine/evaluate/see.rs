@@ -0,0 +1,176 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Static exchange evaluation (SEE).
+//!
+//! SEE estimates the material outcome of a capture by playing out every recapture on the target
+//! square, always recapturing with the least valuable piece.
+//! Either side may stop capturing whenever continuing would lose material.
+//! Sliders lined up behind other attackers (x-rays) join in as the pieces in front of them leave.
+
+use crate::{
+    base::{Bitboard, Board, Move, Piece, Square},
+    engine::evaluate::{material, Eval},
+};
+
+/// The order in which pieces are chosen to recapture: least valuable first.
+const RECAPTURE_ORDER: [Piece; Piece::NUM] = [
+    Piece::Pawn,
+    Piece::Knight,
+    Piece::Bishop,
+    Piece::Rook,
+    Piece::Queen,
+    Piece::King,
+];
+
+#[must_use]
+#[allow(clippy::missing_panics_doc)]
+/// Compute the static exchange evaluation of the move `m` on `board`: the net material gained by
+/// the player making the move, in centipawns, once every profitable recapture on its target square
+/// has been played out.
+/// Quiet moves are treated as capturing nothing, so their SEE is zero or negative.
+/// Requires that `m` must be a legal move in `board`.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, Move, Square};
+/// use tomato::engine::evaluate::{see::see, Eval};
+///
+/// // the pawn on d5 is defended, so taking it with the queen loses material
+/// let b = Board::from_fen("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1").unwrap();
+/// assert!(see(&b, Move::normal(Square::D1, Square::D5)) < Eval::DRAW);
+/// ```
+pub fn see(board: &Board, m: Move) -> Eval {
+    let to_sq = m.to_square();
+    let from_sq = m.from_square();
+
+    let mut occupancy = board.occupancy();
+    occupancy -= Bitboard::from(from_sq);
+    let victim = if m.is_en_passant() {
+        // the captured pawn is beside the mover, on the same rank as it started
+        let capturee_sq = Square::new(from_sq.rank(), to_sq.file()).unwrap();
+        occupancy -= Bitboard::from(capturee_sq);
+        Some(Piece::Pawn)
+    } else {
+        board.type_at_square(to_sq)
+    };
+
+    // gains[i] is the material balance after the i-th capture, from the point of view of the side
+    // making that capture
+    let mut gains = Vec::with_capacity(32);
+    gains.push(victim.map_or(Eval::DRAW, |pt| material::value(pt).midgame()));
+
+    // the piece currently standing on the target square, which the next capture will win
+    let mut on_target = board.type_at_square(from_sq).unwrap();
+    let mut side = !board.player;
+    loop {
+        let attackers = board.attackers_to(to_sq, occupancy) & board[side];
+        let Some((attacker_type, attacker_sq)) = RECAPTURE_ORDER
+            .iter()
+            .find_map(|&pt| (attackers & board[pt]).lsb().map(|sq| (pt, sq)))
+        else {
+            break;
+        };
+
+        if attacker_type == Piece::King {
+            // the king may only recapture if the square is no longer defended
+            let mut after = occupancy;
+            after -= Bitboard::from(attacker_sq);
+            if !(board.attackers_to(to_sq, after) & board[!side]).is_empty() {
+                break;
+            }
+        }
+
+        let last = gains[gains.len() - 1];
+        gains.push(material::value(on_target).midgame() - last);
+        occupancy -= Bitboard::from(attacker_sq);
+        on_target = attacker_type;
+        side = !side;
+    }
+
+    // each side may decline to make a capture which would leave it worse off
+    while gains.len() > 1 {
+        let reply = gains.pop().unwrap();
+        let idx = gains.len() - 1;
+        gains[idx] = -((-gains[idx]).max(reply));
+    }
+
+    gains[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test that capturing a defended pawn with a queen loses material.
+    fn defended_pawn() {
+        let b = Board::from_fen("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1").unwrap();
+        assert_eq!(
+            see(&b, Move::normal(Square::D1, Square::D5)),
+            material::value(Piece::Pawn).midgame() - material::value(Piece::Queen).midgame()
+        );
+    }
+
+    #[test]
+    /// Test that capturing an undefended piece wins the whole piece.
+    fn undefended_piece() {
+        let b = Board::from_fen("4k3/8/8/3r4/8/8/8/3QK3 w - - 0 1").unwrap();
+        assert_eq!(
+            see(&b, Move::normal(Square::D1, Square::D5)),
+            material::value(Piece::Rook).midgame()
+        );
+    }
+
+    #[test]
+    /// Test that a rook lined up behind another rook joins the exchange once the front rook has
+    /// captured.
+    fn xray_battery() {
+        // Rxd5 Rxd5 Rxd5 wins a knight, but only because of the rook on d1
+        let b = Board::from_fen("3rk3/8/8/3n4/8/8/3R4/3RK3 w - - 0 1").unwrap();
+        assert_eq!(
+            see(&b, Move::normal(Square::D2, Square::D5)),
+            material::value(Piece::Knight).midgame()
+        );
+        let b = Board::from_fen("3rk3/8/8/3n4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            see(&b, Move::normal(Square::D2, Square::D5)),
+            material::value(Piece::Knight).midgame() - material::value(Piece::Rook).midgame()
+        );
+    }
+
+    #[test]
+    /// Test that a king cannot recapture on a square which is still defended.
+    fn king_cannot_recapture_defended() {
+        // after Bxf7, Kxf7 would walk into the queen on f3
+        let b = Board::from_fen("4k3/5p2/8/8/2B5/5Q2/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            see(&b, Move::normal(Square::C4, Square::F7)),
+            material::value(Piece::Pawn).midgame()
+        );
+    }
+
+    #[test]
+    /// Test that an en passant capture wins a pawn.
+    fn en_passant() {
+        let b = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let m = Move::en_passant(Square::E5, Square::D6);
+        assert_eq!(see(&b, m), material::value(Piece::Pawn).midgame());
+    }
+}
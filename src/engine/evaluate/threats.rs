@@ -0,0 +1,231 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Threats against pieces.
+//!
+//! A threat is a way of attacking an enemy piece which the enemy must respond to.
+//! Three kinds of threat are considered:
+//!
+//! * a pawn which can safely push to a square from which it attacks an enemy piece,
+//! * a pawn which already attacks an enemy piece, which is a threat whether or not the piece is
+//!   defended, and
+//! * any other attack on an enemy piece which is not defended at all.
+
+use crate::{
+    base::{
+        movegen::{piece_attacks, PAWN_ATTACKS},
+        Bitboard, Board, Color, Piece,
+    },
+    engine::evaluate::Score,
+};
+
+/// The bonus for each enemy piece which could be attacked by a safe pawn push.
+pub const PAWN_PUSH_THREAT: Score = Score::centipawns(15, 12);
+
+/// The bonus for attacking an enemy piece with a pawn, indexed by the type of the attacked piece.
+/// Pawns are so cheap that trading one for anything else is a win, so these apply even if the
+/// attacked piece is defended.
+pub const PAWN_ATTACK_THREAT: [Score; Piece::NUM] = [
+    Score::centipawns(40, 30), // N
+    Score::centipawns(40, 30), // B
+    Score::centipawns(55, 40), // R
+    Score::centipawns(65, 45), // Q
+    Score::centipawns(0, 0),   // P
+    Score::centipawns(0, 0),   // K
+];
+
+/// The bonus for attacking an undefended enemy piece, indexed by the type of the attacked piece.
+pub const HANGING: [Score; Piece::NUM] = [
+    Score::centipawns(30, 20), // N
+    Score::centipawns(30, 20), // B
+    Score::centipawns(45, 30), // R
+    Score::centipawns(70, 50), // Q
+    Score::centipawns(8, 12),  // P
+    Score::centipawns(0, 0),   // K
+];
+
+#[must_use]
+/// Evaluate a position based on the threats each side can make.
+pub fn evaluate(b: &Board) -> Score {
+    pawn_push_threats(b, Color::White) - pawn_push_threats(b, Color::Black) + piece_threats(b)
+}
+
+#[must_use]
+/// Evaluate the threats each side makes against the other's pieces, from White's point of view.
+/// Each attacked piece is only counted once: a piece attacked by a pawn gets the
+/// `PAWN_ATTACK_THREAT` bonus, and otherwise an undefended piece gets the `HANGING` bonus.
+/// Kings are never counted.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, Piece};
+/// use tomato::engine::evaluate::threats::{piece_threats, HANGING};
+///
+/// // the bishop on b2 attacks the undefended rook on g7
+/// let b = Board::from_fen("k7/6r1/8/8/8/8/1B6/7K w - - 0 1").unwrap();
+/// assert_eq!(piece_threats(&b), HANGING[Piece::Rook as usize]);
+/// ```
+pub fn piece_threats(b: &Board) -> Score {
+    piece_threats_for(b, Color::White) - piece_threats_for(b, Color::Black)
+}
+
+#[must_use]
+/// Get the set of squares attacked by the pieces of `color`, including squares occupied by
+/// `color`'s own pieces (which are therefore defended).
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, Color, Square};
+/// use tomato::engine::evaluate::threats::attacked_by;
+///
+/// let b = Board::new();
+/// assert!(attacked_by(&b, Color::White).contains(Square::F3));
+/// assert!(!attacked_by(&b, Color::White).contains(Square::E4));
+/// ```
+pub fn attacked_by(b: &Board, color: Color) -> Bitboard {
+    let occupancy = b.occupancy();
+    let mut attacked = Bitboard::EMPTY;
+    for pt in Piece::ALL {
+        for sq in b[pt] & b[color] {
+            attacked |= piece_attacks(pt, color, sq, occupancy);
+        }
+    }
+
+    attacked
+}
+
+#[must_use]
+/// Get the set of enemy pieces which `color` can attack by safely pushing a pawn one square.
+/// A push square is safe if it is not attacked by an enemy pawn and is either defended by `color`
+/// or not attacked by the enemy at all.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::{Board, Color, Square};
+/// use tomato::engine::evaluate::threats::push_threatened;
+///
+/// // pushing d4-d5 would attack the knight on c6
+/// let b = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/3P4/8/PPP1PPPP/RNBQKBNR w KQkq - 0 3")
+///     .unwrap();
+/// assert!(push_threatened(&b, Color::White).contains(Square::C6));
+/// ```
+pub fn push_threatened(b: &Board, color: Color) -> Bitboard {
+    let occupancy = b.occupancy();
+    let pawns = b[Piece::Pawn] & b[color];
+    let pushed = match color {
+        Color::White => pawns << 8,
+        Color::Black => pawns >> 8,
+    } & !occupancy;
+
+    let mut enemy_pawn_attacks = Bitboard::EMPTY;
+    for sq in b[Piece::Pawn] & b[!color] {
+        enemy_pawn_attacks |= PAWN_ATTACKS[!color as usize][sq as usize];
+    }
+
+    let targets = b[!color] & !(b[Piece::Pawn] | b[Piece::King]);
+    let mut threatened = Bitboard::EMPTY;
+    for sq in pushed & !enemy_pawn_attacks {
+        if b.is_square_attacked(sq, !color) && !b.is_square_attacked(sq, color) {
+            continue;
+        }
+        threatened |= PAWN_ATTACKS[color as usize][sq as usize] & targets;
+    }
+
+    threatened
+}
+
+/// Get the threats `color` makes against the enemy's pieces, from `color`'s point of view.
+fn piece_threats_for(b: &Board, color: Color) -> Score {
+    let mut pawn_attacks = Bitboard::EMPTY;
+    for sq in b[Piece::Pawn] & b[color] {
+        pawn_attacks |= PAWN_ATTACKS[color as usize][sq as usize];
+    }
+    let victims = b[!color] & !b[Piece::King];
+    let pawn_attacked = victims & pawn_attacks;
+    let hanging = (victims & attacked_by(b, color)) - attacked_by(b, !color) - pawn_attacked;
+
+    let mut score = Score::DRAW;
+    for pt in Piece::NON_KING {
+        score += PAWN_ATTACK_THREAT[pt as usize] * (pawn_attacked & b[pt]).len();
+        score += HANGING[pt as usize] * (hanging & b[pt]).len();
+    }
+
+    score
+}
+
+/// Get the pawn push threat bonus for one color, from that color's point of view.
+fn pawn_push_threats(b: &Board, color: Color) -> Score {
+    PAWN_PUSH_THREAT * push_threatened(b, color).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::Square;
+
+    #[test]
+    /// Test that a pawn push attacking a knight is registered as a threat.
+    fn push_attacks_knight() {
+        let b = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/3P4/8/PPP1PPPP/RNBQKBNR w KQkq - 0 3")
+            .unwrap();
+        assert_eq!(
+            push_threatened(&b, Color::White),
+            Bitboard::from(Square::C6)
+        );
+        assert_eq!(evaluate(&b), PAWN_PUSH_THREAT);
+    }
+
+    #[test]
+    /// Test that a pawn push onto a square defended by an enemy pawn is not a threat.
+    fn push_into_pawn_defense() {
+        // d4-d5 would attack the knight on c6, but the pawn on e6 covers d5
+        let b = Board::from_fen("r1bqkbnr/pppp1ppp/2n1p3/8/3P4/8/PPP1PPPP/RNBQKBNR w KQkq - 0 3")
+            .unwrap();
+        assert!(push_threatened(&b, Color::White).is_empty());
+    }
+
+    #[test]
+    /// Test that a rook hanging to a bishop is a bigger threat than a defended one.
+    fn hanging_rook() {
+        let hanging = Board::from_fen("k7/6r1/8/8/8/8/1B6/7K w - - 0 1").unwrap();
+        // the knight on e8 defends the rook
+        let defended = Board::from_fen("k3n3/6r1/8/8/8/8/1B6/7K w - - 0 1").unwrap();
+        assert_eq!(piece_threats(&hanging), HANGING[Piece::Rook as usize]);
+        assert_eq!(piece_threats(&defended), Score::DRAW);
+        assert!(piece_threats(&hanging).blend(0.5) > piece_threats(&defended).blend(0.5));
+    }
+
+    #[test]
+    /// Test that a pawn attack on an undefended piece is only counted once, as a pawn attack.
+    fn pawn_attack_not_double_counted() {
+        let b = Board::from_fen("4k3/8/2n5/1P6/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            piece_threats(&b),
+            PAWN_ATTACK_THREAT[Piece::Knight as usize]
+        );
+    }
+
+    #[test]
+    /// Test that the starting position has no threats.
+    fn start_no_threats() {
+        assert_eq!(evaluate(&Board::new()), Score::DRAW);
+    }
+}
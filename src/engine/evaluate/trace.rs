@@ -0,0 +1,227 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Evaluation traces.
+//!
+//! A single evaluation number hides which rules produced it.
+//! A trace records the contribution of every rule separately, so that the evaluation of a position
+//! can be inspected term by term when debugging or tuning.
+
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    base::{Board, Color},
+    engine::evaluate::{
+        center, development, king, material, mobility, outposts, passed, pawns, phase_of, pst,
+        rooks, scale, threats, trapped, Eval, Score, TEMPO,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The contribution of every evaluation rule to the evaluation of a position.
+/// Each term is from White's point of view, before endgame scaling and phase blending.
+pub struct EvalTrace {
+    /// Material, not including the bishop pair.
+    pub material: Score,
+    /// The piece-square tables.
+    pub pst: Score,
+    /// Development of the minor pieces.
+    pub development: Score,
+    /// Occupation and control of the central squares.
+    pub center: Score,
+    /// Space behind each side's pawns.
+    pub space: Score,
+    /// King safety and king tropism.
+    pub king: Score,
+    /// Piece mobility.
+    pub mobility: Score,
+    /// Passed pawns.
+    pub passed: Score,
+    /// Minor pieces on outposts or shielded behind a pawn, and the bishop pair.
+    pub structural: Score,
+    /// Pawn structure.
+    pub pawns: Score,
+    /// Rook placement.
+    pub rooks: Score,
+    /// Threats against pieces.
+    pub threats: Score,
+    /// Bishops trapped by enemy pawns.
+    pub trapped: Score,
+    /// The phase of the game, from 0 (full endgame) to 1 (full midgame).
+    pub phase: f32,
+    /// The factor by which the endgame half of the total is scaled, out of `SCALE_NORMAL`.
+    pub scale_factor: u8,
+    /// The player to move.
+    pub player: Color,
+}
+
+#[must_use]
+/// Evaluate a position, recording the contribution of every rule.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::{evaluate, trace::evaluate_trace};
+///
+/// let b = Board::new();
+/// assert_eq!(evaluate_trace(&b).eval(), evaluate(&b));
+/// ```
+pub fn evaluate_trace(b: &Board) -> EvalTrace {
+    trace_with(b, pst::evaluate(b), pawns::evaluate(b))
+}
+
+/// Evaluate a position, recording the contribution of every rule, where `pst` and `pawns` are the
+/// position's already-computed PST and pawn structure scores.
+pub(super) fn trace_with(b: &Board, pst: Score, pawns: Score) -> EvalTrace {
+    EvalTrace {
+        material: material::evaluate(b),
+        pst,
+        development: development::evaluate(b),
+        center: center::center_control(b),
+        space: center::space(b),
+        king: king::evaluate(b),
+        mobility: mobility::evaluate(b),
+        passed: passed::evaluate(b),
+        structural: outposts::structural(b),
+        pawns,
+        rooks: rooks::evaluate(b),
+        threats: threats::evaluate(b),
+        trapped: trapped::evaluate(b),
+        phase: phase_of(b),
+        scale_factor: scale::scale_factor(b),
+        player: b.player,
+    }
+}
+
+impl EvalTrace {
+    #[must_use]
+    /// Get the name and value of every term in this trace, in the order they are summed.
+    pub const fn terms(&self) -> [(&'static str, Score); 13] {
+        [
+            ("material", self.material),
+            ("pst", self.pst),
+            ("development", self.development),
+            ("center", self.center),
+            ("space", self.space),
+            ("king", self.king),
+            ("mobility", self.mobility),
+            ("passed", self.passed),
+            ("structural", self.structural),
+            ("pawns", self.pawns),
+            ("rooks", self.rooks),
+            ("threats", self.threats),
+            ("trapped", self.trapped),
+        ]
+    }
+
+    #[must_use]
+    /// Get the sum of every term, from White's point of view, before scaling and blending.
+    pub fn total(&self) -> Score {
+        self.terms()
+            .iter()
+            .fold(Score::DRAW, |total, &(_, score)| total + score)
+    }
+
+    #[must_use]
+    /// Get the scaled and blended evaluation of the traced position, from White's point of view.
+    pub fn blended(&self) -> Eval {
+        scale::scale_by(self.total(), self.scale_factor).blend(self.phase)
+    }
+
+    #[must_use]
+    /// Get the scaled and blended evaluation of the traced position from White's point of view,
+    /// including the tempo bonus for the player to move.
+    pub fn blended_with_tempo(&self) -> Eval {
+        self.blended() + TEMPO.blend(self.phase).in_perspective(self.player)
+    }
+
+    #[must_use]
+    /// Get the evaluation of the traced position from the point of view of the player to move,
+    /// including the tempo bonus.
+    /// This is the same as the result of `evaluate()` on the position.
+    pub fn eval(&self) -> Eval {
+        self.blended_with_tempo().in_perspective(self.player)
+    }
+}
+
+impl Display for EvalTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<12}{:>8}{:>8}", "term", "mg", "eg")?;
+        for (name, score) in self.terms() {
+            writeln!(
+                f,
+                "{name:<12}{:>8}{:>8}",
+                score.midgame().to_string(),
+                score.endgame().to_string()
+            )?;
+        }
+        let total = self.total();
+        writeln!(
+            f,
+            "{:<12}{:>8}{:>8}",
+            "total",
+            total.midgame().to_string(),
+            total.endgame().to_string()
+        )?;
+        writeln!(
+            f,
+            "phase {:.2}, scale {}/{}",
+            self.phase,
+            self.scale_factor,
+            scale::SCALE_NORMAL
+        )?;
+        write!(
+            f,
+            "eval {} (White), {} (to move)",
+            self.blended(),
+            self.eval()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::evaluate::evaluate;
+
+    #[test]
+    /// Test that summing and blending a trace gives the same result as the scalar evaluation.
+    fn trace_matches_evaluate() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r1bq1b1r/ppp2kpp/2n5/3np3/2B5/8/PPPP1PPP/RNBQK2R w KQ - 0 7",
+            "2k5/pp3pp1/2p1pr2/Pn2b3/1P1P1P1r/2p1P1N1/6R1/3R2K1 b - - 0 1",
+            "2b1k3/5pp1/8/8/8/5PPP/8/4KB2 w - - 0 1",
+        ] {
+            let b = Board::from_fen(fen).unwrap();
+            assert_eq!(evaluate_trace(&b).eval(), evaluate(&b));
+        }
+    }
+
+    #[test]
+    /// Test that the displayed table has a row for every term.
+    fn display_rows() {
+        let trace = evaluate_trace(&Board::new());
+        let s = trace.to_string();
+        for (name, _) in trace.terms() {
+            assert!(s.lines().any(|l| l.starts_with(name)));
+        }
+        assert!(s.lines().any(|l| l.starts_with("total")));
+    }
+}
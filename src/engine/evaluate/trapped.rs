@@ -0,0 +1,102 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Trapped bishops.
+//!
+//! A bishop which grabs a pawn on a7 or h7 can be shut in by an enemy pawn stepping up to b6 or g6.
+//! Its only way out is back through the square that pawn now controls, so it is usually lost.
+//! Mobility alone does not catch this, since the bishop still seems to have a square or two.
+//! Only a handful of well-known patterns are recognized, to avoid penalizing bishops which are
+//! merely passive.
+
+use crate::{
+    base::{Board, Color, Piece, Square},
+    engine::evaluate::Score,
+};
+
+/// The penalty for a bishop trapped by an enemy pawn.
+pub const TRAPPED_BISHOP: Score = Score::centipawns(-120, -100);
+
+/// The trapped-bishop patterns from White's point of view: a bishop on the first square is trapped
+/// by an enemy pawn on the second.
+/// Black's patterns are the same, flipped vertically.
+const PATTERNS: [(Square, Square); 4] = [
+    (Square::A7, Square::B6),
+    (Square::H7, Square::G6),
+    (Square::B8, Square::C7),
+    (Square::G8, Square::F7),
+];
+
+#[must_use]
+/// Evaluate a position based on the bishops each side has trapped by enemy pawns.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::base::Board;
+/// use tomato::engine::evaluate::trapped::{evaluate, TRAPPED_BISHOP};
+///
+/// let b = Board::from_fen("4k3/B1p5/1p6/8/8/8/8/4K3 w - - 0 1").unwrap();
+/// assert_eq!(evaluate(&b), TRAPPED_BISHOP);
+/// ```
+pub fn evaluate(b: &Board) -> Score {
+    trapped_for(b, Color::White) - trapped_for(b, Color::Black)
+}
+
+/// Get the trapped bishop penalty for one color, from that color's point of view.
+fn trapped_for(b: &Board, color: Color) -> Score {
+    let bishops = b[Piece::Bishop] & b[color];
+    let enemy_pawns = b[Piece::Pawn] & b[!color];
+    let mut score = Score::DRAW;
+    for (bishop_sq, pawn_sq) in PATTERNS {
+        let (bishop_sq, pawn_sq) = match color {
+            Color::White => (bishop_sq, pawn_sq),
+            Color::Black => (bishop_sq.opposite(), pawn_sq.opposite()),
+        };
+        if bishops.contains(bishop_sq) && enemy_pawns.contains(pawn_sq) {
+            score += TRAPPED_BISHOP;
+        }
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test that a bishop shut in on a7 by a pawn on b6 is penalized, but a free bishop is not.
+    fn trapped_vs_free() {
+        let trapped = Board::from_fen("4k3/B1p5/1p6/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let free = Board::from_fen("4k3/B1p5/8/1p6/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(evaluate(&trapped), TRAPPED_BISHOP);
+        assert_eq!(evaluate(&free), Score::DRAW);
+
+        // a bishop on a7 with the pawn on b6 being its own is not trapped
+        let own_pawn = Board::from_fen("4k3/B7/1P6/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(evaluate(&own_pawn), Score::DRAW);
+    }
+
+    #[test]
+    /// Test that trapped Black bishops count in White's favor.
+    fn black_trapped() {
+        let b = Board::from_fen("4k3/8/8/8/8/6P1/7b/4K3 w - - 0 1").unwrap();
+        assert_eq!(evaluate(&b), Score::DRAW - TRAPPED_BISHOP);
+    }
+}
@@ -0,0 +1,253 @@
+/*
+  Tomato, a UCI-compatible chess engine.
+  Copyright (C) 2022 Clayton Ramsey.
+
+  Tomato is free software: you can redistribute it and/or modify
+  it under the terms of the GNU General Public License as published by
+  the Free Software Foundation, either version 3 of the License, or
+  (at your option) any later version.
+
+  Tomato is distributed in the hope that it will be useful,
+  but WITHOUT ANY WARRANTY; without even the implied warranty of
+  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+  GNU General Public License for more details.
+
+  You should have received a copy of the GNU General Public License
+  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Texel tuning for the piece-square tables.
+//!
+//! Texel tuning measures how well the static evaluation predicts the results of a set of labeled
+//! positions, then nudges one table entry at a time, keeping each nudge only if it makes the
+//! prediction better.
+//! Unlike the gradient-descent tuner binary, this uses the full evaluation, so every other term is
+//! taken into account while tuning.
+
+use std::{fmt::Write, io::BufRead};
+
+use crate::{
+    base::{Board, Color, Piece, Square},
+    engine::evaluate::{
+        evaluate_board_with,
+        pst::{mirror, Pst},
+        Eval, Score,
+    },
+};
+
+/// A position labeled with the result of the game it came from, from White's point of view.
+/// A White win is 1, a draw is 0.5, and a Black win is 0.
+pub type Datum = (Board, f32);
+
+/// Load a set of labeled positions from `reader`.
+/// Each line must be in the same format as the EPD files used by the tuner binary: a FEN, followed
+/// by the game result in quotes (`"1-0"`, `"0-1"`, or `"1/2-1/2"`) and a semicolon.
+/// Empty lines are skipped.
+///
+/// # Errors
+///
+/// This function will return an error if a line could not be read, or if it contains an invalid
+/// FEN or result.
+///
+/// # Examples
+///
+/// ```
+/// use tomato::engine::evaluate::tuning::load_positions;
+///
+/// let data = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 \"1/2-1/2\";";
+/// let positions = load_positions(data.as_bytes()).unwrap();
+/// assert_eq!(positions.len(), 1);
+/// assert!(positions[0].1.eq(&0.5));
+/// ```
+pub fn load_positions(reader: impl BufRead) -> Result<Vec<Datum>, &'static str> {
+    let mut positions = Vec::new();
+
+    for line_result in reader.lines() {
+        let line = line_result.map_err(|_| "unable to read line")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut split_line = line.split('"');
+        let fen = split_line.next().ok_or("no FEN given")?;
+        let board = Board::from_fen(fen.trim())?;
+        let result = match split_line.next().ok_or("no result given")? {
+            "1-0" => 1.,
+            "1/2-1/2" => 0.5,
+            "0-1" => 0.,
+            _ => return Err("unknown result string"),
+        };
+        positions.push((board, result));
+    }
+
+    Ok(positions)
+}
+
+#[inline(always)]
+/// Convert an evaluation into an expected score, from 0 (a sure loss) to 1 (a sure win).
+fn sigmoid(eval: Eval) -> f32 {
+    1. / (1. + (-eval.float_val()).exp())
+}
+
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+/// Compute the mean squared error of the static evaluation against the results of `positions`,
+/// using `tables` as the piece-square tables.
+/// `tables` contains the table for White followed by its mirror for Black, as built by
+/// `pst::mirror()`.
+/// Returns 0 if `positions` is empty.
+pub fn error(positions: &[Datum], tables: &[Pst; 2]) -> f32 {
+    if positions.is_empty() {
+        return 0.;
+    }
+    let sum_se: f32 = positions
+        .iter()
+        .map(|(board, result)| {
+            let err = result - sigmoid(evaluate_board_with(board, tables));
+            err * err
+        })
+        .sum();
+
+    sum_se / positions.len() as f32
+}
+
+/// Run up to `iterations` passes of coordinate descent over the entries of `table`, minimizing
+/// its error on `positions`.
+/// Each entry is stepped by one centipawn up or down, and a step is only kept if it strictly
+/// reduces the error, so the error never increases.
+/// Entries for pieces and squares which do not appear in any position cannot change the error, so
+/// they are left alone.
+/// Tuning stops early if a pass fails to improve any entry.
+///
+/// Returns the error of the tuned table.
+pub fn tune(positions: &[Datum], table: &mut Pst, iterations: usize) -> f32 {
+    // mark which entries are actually used by the dataset
+    let mut used = [[false; 64]; Piece::NUM];
+    for (board, _) in positions {
+        for pt in Piece::ALL {
+            for sq in board[pt] & board[Color::White] {
+                used[pt as usize][sq as usize] = true;
+            }
+            for sq in board[pt] & board[Color::Black] {
+                used[pt as usize][sq.opposite() as usize] = true;
+            }
+        }
+    }
+
+    // keep the mirrored table for Black in step with `table`, instead of rebuilding it for every
+    // call to `error()`
+    let mut tables = [*table, mirror(table)];
+    let step = Eval::centipawns(1);
+    let mut best_err = error(positions, &tables);
+    for _ in 0..iterations {
+        let mut improved = false;
+        for pt in Piece::ALL {
+            for sq_idx in 0..64 {
+                if !used[pt as usize][sq_idx] {
+                    continue;
+                }
+                // flipping the rank gives the same entry in the mirrored table
+                let mirror_idx = sq_idx ^ 0x38;
+                for endgame in [false, true] {
+                    let original = tables[0][pt as usize][sq_idx];
+                    for delta in [step, -step] {
+                        let stepped = if endgame {
+                            Score::new(original.midgame(), original.endgame() + delta)
+                        } else {
+                            Score::new(original.midgame() + delta, original.endgame())
+                        };
+                        tables[0][pt as usize][sq_idx] = stepped;
+                        tables[1][pt as usize][mirror_idx] = stepped;
+                        let err = error(positions, &tables);
+                        if err < best_err {
+                            best_err = err;
+                            improved = true;
+                            break;
+                        }
+                        tables[0][pt as usize][sq_idx] = original;
+                        tables[1][pt as usize][mirror_idx] = original;
+                    }
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+
+    *table = tables[0];
+    best_err
+}
+
+#[must_use]
+#[allow(clippy::missing_panics_doc)]
+/// Format `table` as Rust source, in the same `(i16, i16)` layout as the `PST` constant, so that a
+/// tuned table can be pasted directly into `pst.rs`.
+pub fn format_pst(table: &Pst) -> String {
+    let mut s = String::from("pub const PST: Pst = from_pairs(&[\n");
+    for pt in Piece::ALL {
+        writeln!(s, "    [ // {pt}").unwrap();
+        for rank in 0..8 {
+            s.push_str("        ");
+            for file in 0..8 {
+                let sq = Square::new(rank, file).unwrap();
+                let score = table[pt as usize][sq as usize];
+                write!(
+                    s,
+                    "({}, {}), ",
+                    score.midgame().centipawn_val(),
+                    score.endgame().centipawn_val()
+                )
+                .unwrap();
+            }
+            s.push('\n');
+        }
+        s.push_str("    ],\n");
+    }
+    s.push_str("]);\n");
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::evaluate::pst::PST;
+
+    /// A tiny synthetic dataset, labeled so that the built-in table is not already optimal.
+    const DATA: &str = "\
+rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 \"1/2-1/2\";
+rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1 \"1-0\";
+rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2 \"0-1\";
+4k3/8/8/8/8/8/4P3/4K3 w - - 0 1 \"1-0\";
+";
+
+    #[test]
+    /// Test that a few iterations of tuning do not increase the error.
+    fn error_does_not_increase() {
+        let positions = load_positions(DATA.as_bytes()).unwrap();
+        let mut table = PST;
+        let before = error(&positions, &[table, mirror(&table)]);
+        let after = tune(&positions, &mut table, 3);
+        assert!(after <= before);
+        assert!(after.eq(&error(&positions, &[table, mirror(&table)])));
+    }
+
+    #[test]
+    /// Test that the formatted table has one line for each rank of each piece.
+    fn format_dimensions() {
+        let s = format_pst(&PST);
+        let rows = s
+            .lines()
+            .filter(|l| l.trim_start().starts_with('('))
+            .count();
+        assert_eq!(rows, 8 * Piece::NUM);
+        assert!(s.contains("(0, 0), "));
+    }
+
+    #[test]
+    /// Test that malformed lines are rejected.
+    fn bad_result() {
+        let data = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1 \"2-0\";";
+        assert!(load_positions(data.as_bytes()).is_err());
+    }
+}
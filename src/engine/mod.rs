@@ -26,7 +26,7 @@ use search::SearchError;
 
 pub mod evaluate;
 pub mod limit;
-mod pick;
+pub mod pick;
 mod search;
 pub mod thread;
 pub mod time;
@@ -22,10 +22,11 @@
 //! puts the best moves first.
 //! This move ordering is the move picker's job.
 
-use crate::base::{Board, Move};
+use crate::base::{Board, Move, Piece};
 
-use super::evaluate::{material, Eval, Score};
+use super::evaluate::{material, mobility, pst, Eval, Score};
 
+#[must_use]
 /// Create an estimate for how good a move is.
 /// `delta` is the PST difference created by this move.
 /// Requires that `m` must be a legal move in `b`.
@@ -42,3 +43,73 @@ pub fn candidacy(b: &Board, m: Move, delta: Score, phase: f32) -> Eval {
     worst_case_delta -= mover_value;
     worst_case_delta.blend(phase)
 }
+
+#[must_use]
+/// Compute the positional gain of a move for ordering: the sum of its PST and mobility deltas.
+/// Returns both the combined `Score` and its blended `Eval` at the given phase.
+/// Like `pst::delta`, the result is from the point of view of the player making the move.
+/// Requires that `m` must be a legal move in `b`.
+pub fn positional_delta(b: &Board, m: Move, phase: f32) -> (Score, Eval) {
+    let delta = pst::delta(b, m) + mobility::delta(b, m);
+    (delta, delta.blend(phase))
+}
+
+#[must_use]
+#[allow(clippy::missing_panics_doc)]
+/// Compute the most-valuable-victim, least-valuable-attacker (MVV-LVA) key of a move, for ordering
+/// captures.
+/// A higher key means the move should be searched earlier.
+/// Captures are ranked by the value of the captured piece minus the value of the capturing piece,
+/// and non-captures always sort last.
+/// Requires that `m` must be a legal move in `b`.
+pub fn mvv_lva(b: &Board, m: Move) -> Eval {
+    let victim_type = if m.is_en_passant() {
+        Piece::Pawn
+    } else {
+        match b.type_at_square(m.to_square()) {
+            Some(pt) => pt,
+            None => return Eval::MIN,
+        }
+    };
+    let attacker_type = b.type_at_square(m.from_square()).unwrap();
+
+    material::value(victim_type).midgame() - material::value(attacker_type).midgame()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{
+        movegen::{get_moves, GenMode},
+        Square,
+    };
+
+    #[test]
+    /// Test that the positional delta of a move is the sum of its PST and mobility deltas.
+    fn positional_delta_sums() {
+        let b =
+            Board::from_fen("r1bq1b1r/ppp2kpp/2n5/3np3/2B5/8/PPPP1PPP/RNBQK2R w KQ - 0 7").unwrap();
+        for m in get_moves::<{ GenMode::All }>(&b).into_iter().take(10) {
+            let expected = pst::delta(&b, m) + mobility::delta(&b, m);
+            let (score, eval) = positional_delta(&b, m, 0.5);
+            assert_eq!(score, expected);
+            assert_eq!(eval, expected.blend(0.5));
+        }
+    }
+
+    #[test]
+    /// Test that pawn-takes-queen is ordered ahead of queen-takes-pawn, and that both are ordered
+    /// ahead of quiet moves.
+    fn mvv_lva_order() {
+        // White can play exd5 (winning a queen) or Qxh7 (winning a pawn)
+        let b = Board::from_fen("4k3/7p/8/3q4/4P3/8/8/4K2Q w - - 0 1").unwrap();
+        let mut moves = get_moves::<{ GenMode::All }>(&b);
+        moves.sort_by_key(|&m| -mvv_lva(&b, m));
+
+        assert_eq!(moves[0], Move::normal(Square::E4, Square::D5));
+        assert_eq!(moves[1], Move::normal(Square::H1, Square::H7));
+        for &m in &moves[2..] {
+            assert_eq!(mvv_lva(&b, m), Eval::MIN);
+        }
+    }
+}
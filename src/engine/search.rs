@@ -35,7 +35,7 @@ use crate::{
         Move,
     },
     engine::{
-        evaluate::{material, phase_of, pst},
+        evaluate::{cache::PawnTable, material, phase_of, pst, scored::ScoredGame, Score},
         pick::candidacy,
     },
 };
@@ -51,6 +51,9 @@ use super::{
 
 use std::{cmp::max, sync::PoisonError};
 
+/// The number of slots in the pawn table of each search.
+const PAWN_TABLE_SIZE: usize = 1 << 12;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(clippy::module_name_repetitions)]
 /// The types of errors which can occur during a search.
@@ -153,8 +156,10 @@ impl SearchInfo {
 /// A structure containing data which is shared across function calls to a principal variation
 /// search.
 struct PVSearch<'a> {
-    /// The game being searched.
-    game: Game,
+    /// The game being searched, along with the PST score of each position on the current line.
+    game: ScoredGame,
+    /// The cache of pawn structure evaluations for this search.
+    pawn_table: PawnTable,
     /// The transposition table.
     ttable: &'a TTable,
     /// The cumulative number of nodes evaluated in this evaluation.
@@ -181,7 +186,8 @@ impl<'a> PVSearch<'a> {
         limit: &'a SearchLimit,
     ) -> PVSearch<'a> {
         PVSearch {
-            game,
+            game: ScoredGame::new(game),
+            pawn_table: PawnTable::with_capacity(PAWN_TABLE_SIZE),
             ttable,
             num_nodes_evaluated: 0,
             nodes_since_limit_update: 0,
@@ -281,13 +287,13 @@ impl<'a> PVSearch<'a> {
         }
 
         // detect draws.
-        if self.game.drawn_by_repetition() || self.game.board().is_drawn() {
+        if self.game.game().drawn_by_repetition() || self.game.board().is_drawn() {
             if PV {
                 line.clear();
             }
             // required so that movepicker only needs to know about current position, and not about
             // history
-            return Ok(Eval::DRAW);
+            return Ok(self.config.draw_score(depth_so_far));
         }
 
         // Retrieve transposition data and use it to improve our estimate on the position
@@ -319,14 +325,20 @@ impl<'a> PVSearch<'a> {
             }
         }
 
-        let mut moves_iter = self.game.get_moves::<{ GenMode::All }>();
         let b = self.game.board();
         let phase = phase_of(b);
-        moves_iter.sort_by_cached_key(|&m| {
+        let mut moves_iter: Vec<(Move, Score)> = self
+            .game
+            .game()
+            .get_moves::<{ GenMode::All }>()
+            .into_iter()
+            .map(|m| (m, pst::delta(b, m)))
+            .collect();
+        moves_iter.sort_by_cached_key(|&(m, pst_delta)| {
             if Some(m) == tt_move {
                 Eval::MIN
             } else {
-                -candidacy(b, m, pst::delta(b, m) + material::delta(b, m), phase)
+                -candidacy(b, m, pst_delta + material::delta(b, m), phase)
             }
         });
         let mut best_move = Move::BAD_MOVE;
@@ -339,9 +351,9 @@ impl<'a> PVSearch<'a> {
         let mut overwrote_alpha = false;
         // The principal variation line, following the best move.
         let mut child_line = Vec::new();
-        for m in moves_iter {
+        for (m, pst_delta) in moves_iter {
             move_count += 1;
-            self.game.make_move(m);
+            self.game.make_move_with_delta(m, pst_delta);
             let mut score = Eval::MIN;
 
             if !PV || move_count > 1 {
@@ -405,7 +417,7 @@ impl<'a> PVSearch<'a> {
             line.clear();
             best_score = if self.game.board().checkers.is_empty() {
                 // stalemated
-                Eval::DRAW
+                self.config.draw_score(depth_so_far)
             } else {
                 // mated
                 lower_bound
@@ -447,12 +459,12 @@ impl<'a> PVSearch<'a> {
         self.selective_depth = max(self.selective_depth, depth_so_far);
 
         // check if the game is over before doing anything
-        if let Some(mated) = self.game.end_state() {
+        if let Some(mated) = self.game.game().end_state() {
             // game is over, quit out immediately
             let score = if mated {
                 -Eval::mate_in(depth_so_far)
             } else {
-                Eval::DRAW
+                self.config.draw_score(depth_so_far)
             };
 
             if PV {
@@ -487,7 +499,7 @@ impl<'a> PVSearch<'a> {
             }
         }
         // capturing is unforced, so we can stop here if the player to move doesn't want to capture.
-        let mut score = leaf_evaluate(&self.game).in_perspective(player);
+        let mut score = leaf_evaluate(&self.game, &mut self.pawn_table).in_perspective(player);
         // println!("{g}: {score}");
 
         // Whether alpha was overwritten by any move at this depth.
@@ -521,17 +533,22 @@ impl<'a> PVSearch<'a> {
         }
 
         let mut best_score = score;
-        let mut moves = self.game.get_moves::<{ GenMode::Captures }>();
-
         let b = self.game.board();
         let phase = phase_of(b);
-        moves.sort_by_cached_key(|&m| {
-            -candidacy(b, m, pst::delta(b, m) + material::delta(b, m), phase)
+        let mut moves: Vec<(Move, Score)> = self
+            .game
+            .game()
+            .get_moves::<{ GenMode::Captures }>()
+            .into_iter()
+            .map(|m| (m, pst::delta(b, m)))
+            .collect();
+        moves.sort_by_cached_key(|&(m, pst_delta)| {
+            -candidacy(b, m, pst_delta + material::delta(b, m), phase)
         });
         let mut child_line = Vec::new();
 
-        for m in moves {
-            self.game.make_move(m);
+        for (m, pst_delta) in moves {
+            self.game.make_move_with_delta(m, pst_delta);
             // zero-window search
             score = -self.quiesce::<false>(
                 depth_so_far + 1,
@@ -729,6 +746,31 @@ pub mod tests {
         );
     }
 
+    #[test]
+    /// Test that contempt makes a stalemate score as a loss for the player to move, and that a
+    /// stalemate is an exact draw with no contempt.
+    fn contempt_stalemate() {
+        let g = Game::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        let mut config = SearchConfig {
+            depth: 2,
+            ..Default::default()
+        };
+        for (contempt, expected) in [(0, 0), (20, -20)] {
+            config.contempt = Eval::centipawns(contempt);
+            let info = search(
+                g.clone(),
+                2,
+                &TTable::with_size(1000),
+                &config,
+                &SearchLimit::default(),
+                Eval::MIN,
+                Eval::MAX,
+            )
+            .unwrap();
+            assert_eq!(info.eval, Eval::centipawns(expected));
+        }
+    }
+
     #[test]
     /// Test that the transposition table contains an entry for the root node of the search.
     fn ttable_populated() {
@@ -50,6 +50,10 @@ pub struct SearchConfig {
     /// The number of nodes which have to be searched before it is worthwhile to update the search
     /// limit with this information.
     pub limit_update_increment: u64,
+    /// How much the engine dislikes draws.
+    /// With positive contempt, a drawn position is scored as slightly losing for the engine, so it
+    /// will play on in roughly equal positions instead of steering toward a draw.
+    pub contempt: Eval,
 }
 
 impl SearchConfig {
@@ -59,6 +63,33 @@ impl SearchConfig {
             depth: 10,
             num_early_moves: 4,
             limit_update_increment: 100,
+            contempt: Eval::DRAW,
+        }
+    }
+
+    #[must_use]
+    /// Get the score of a drawn position, from the perspective of the player to move, reached
+    /// `depth_so_far` plies after the root of the search.
+    /// The player to move at the root is the engine, so a draw is worth `-contempt` on even plies
+    /// and `contempt` on odd plies, when its opponent is the one to move.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tomato::engine::{evaluate::Eval, thread::SearchConfig};
+    ///
+    /// let mut config = SearchConfig::new();
+    /// assert_eq!(config.draw_score(0), Eval::DRAW);
+    ///
+    /// config.contempt = Eval::centipawns(25);
+    /// assert_eq!(config.draw_score(0), Eval::centipawns(-25));
+    /// assert_eq!(config.draw_score(1), Eval::centipawns(25));
+    /// ```
+    pub fn draw_score(&self, depth_so_far: u8) -> Eval {
+        if depth_so_far % 2 == 1 {
+            self.contempt
+        } else {
+            -self.contempt
         }
     }
 }
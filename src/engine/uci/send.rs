@@ -216,17 +216,7 @@ fn write_info(f: &mut fmt::Formatter, infos: &[EngineInfo]) -> fmt::Result {
                 is_lower_bound,
                 is_upper_bound,
             } => {
-                write!(f, " score ")?;
-                match eval.moves_to_mate() {
-                    Some(pl) => {
-                        if eval > &Eval::DRAW {
-                            write!(f, "mate {pl}")?;
-                        } else {
-                            write!(f, "mate -{pl}")?;
-                        }
-                    }
-                    None => write!(f, "cp {}", eval.centipawn_val())?,
-                };
+                write!(f, " score {}", eval.uci_string())?;
                 if *is_lower_bound && !is_upper_bound {
                     write!(f, " lowerbound")?;
                 } else if *is_upper_bound {